@@ -7,9 +7,9 @@
 //!
 //! # Implementation
 //!
-//! A `VecArray` holds data in _either one_ of two storages:
+//! A `VecArray<T, N>` holds data in _either one_ of two storages:
 //!
-//! 1) a fixed-size array of `MAX_ARRAY_SIZE` (defaults to 4) items, and
+//! 1) a fixed-size array of `N` (defaults to `MAX_ARRAY_SIZE`, which is 4) items, and
 //! 2) a dynamic `Vec` with unlimited items.
 //!
 //! At any time, either one of them (or both) must be empty, depending on the capacity of the array.
@@ -18,34 +18,41 @@
 //!
 //! The fixed-size array is not initialized (i.e. initialized with `MaybeUninit::uninit()`).
 //!
-//! When `len <= MAX_ARRAY_SIZE`, all elements are stored in the fixed-size array.
+//! When `len <= N`, all elements are stored in the fixed-size array.
 //! Array slots `>= len` are `MaybeUninit::uninit()` while slots `< len` are considered actual data.
 //! In this scenario, the `Vec` is empty.
 //!
 //! As soon as we try to push a new item into the `VecArray` that makes the total number exceed
-//! `MAX_ARRAY_SIZE`, all the items in the fixed-sized array are taken out, replaced with
+//! `N`, all the items in the fixed-sized array are taken out, replaced with
 //! `MaybeUninit::uninit()` (via `mem::replace`) and pushed into the `Vec`.
 //! Then the new item is added to the `Vec`.
 //!
-//! Therefore, if `len > MAX_ARRAY_SIZE`, then the fixed-size array is considered empty and
+//! Therefore, if `len > N`, then the fixed-size array is considered empty and
 //! uninitialized while all data resides in the `Vec`.
 //!
-//! When popping an item off of the `VecArray`, the reverse is true.  If `len == MAX_ARRAY_SIZE + 1`,
+//! When popping an item off of the `VecArray`, the reverse is true.  If `len == N + 1`,
 //! after popping the item, all the items residing in the `Vec` are moved back to the fixed-size array.
 //! The `Vec` will then be empty.
 //!
-//! Therefore, if `len <= MAX_ARRAY_SIZE`, data is in the fixed-size array.
+//! Therefore, if `len <= N`, data is in the fixed-size array.
 //! Otherwise, data is in the `Vec`.
 //!
+//! The exception is a `VecArray` created via `VecArray::with_sticky_vec`, which keeps data in the `Vec`
+//! once spilled over, until it is emptied entirely.  In all cases, data is in the `Vec` if and only if
+//! the `Vec` is not empty.
+//!
 //! # Limitations
 //!
-//! 1) The constant `MAX_ARRAY_SIZE` must be compiled in, at least until constant generics
-//!    land in Rust.  It defaults to 4; to change it, you must clone this repo and modify the code.
+//! 1) The capacity `N` of the fixed-size array is a constant generic parameter and must be known
+//!    at compile time.  It defaults to `MAX_ARRAY_SIZE` (i.e. 4), so `VecArray<T>` is the same as
+//!    `VecArray<T, 4>`.
 //!
-//! 2) It automatically converts itself into a `Vec` when over `MAX_ARRAY_SIZE` and back into an array
+//! 2) It automatically converts itself into a `Vec` when over `N` and back into an array
 //!    when the number of items drops below this threshold.  If it so happens that the data is constantly
 //!    added and removed from the `VecArray` that straddles this threshold, you'll see excessive
-//!    moving and copying of data back-and-forth, plus allocations and deallocations of the `Vec`.
+//!    moving and copying of data back-and-forth.  (The `Vec` keeps its allocated capacity when data
+//!    is moved back into the array, so it is not re-allocated every time.)
+//!    Use `VecArray::with_sticky_vec` to avoid this, at the expense of memory.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -54,149 +61,444 @@ extern crate alloc;
 
 #[cfg(feature = "std")]
 use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::VecDeque,
     fmt,
     hash::{Hash, Hasher},
-    iter::FromIterator,
+    iter::{self, FromIterator, FusedIterator},
+    marker::PhantomData,
     mem::{self, MaybeUninit},
-    ops::{Deref, DerefMut, Index, IndexMut},
+    ops::{
+        Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeFull,
+        RangeInclusive, RangeTo, RangeToInclusive,
+    },
+    ptr, slice, vec,
 };
 
 #[cfg(not(feature = "std"))]
 use core::{
+    borrow::Borrow,
+    cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
-    iter::FromIterator,
+    iter::{self, FromIterator, FusedIterator},
+    marker::PhantomData,
     mem::{self, MaybeUninit},
-    ops::{Deref, DerefMut, Index, IndexMut},
+    ops::{
+        Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeFull,
+        RangeInclusive, RangeTo, RangeToInclusive,
+    },
+    ptr, slice,
 };
 
 #[cfg(not(feature = "std"))]
-use alloc::{boxed::Box, vec::Vec};
-
-type ArrayStore<T> = [T; MAX_ARRAY_SIZE];
+use alloc::{
+    boxed::Box,
+    collections::VecDeque,
+    vec::{self, Vec},
+};
 
 /// An array-like type that holds a number of values in static storage for no-allocation, quick access.
 ///
+/// Up to `N` values are held in a fixed-size array.  `N` defaults to `MAX_ARRAY_SIZE`.
+///
 /// # Safety
 ///
 /// This type uses some unsafe code (mainly for uninitialized/unused array slots) for efficiency.
-pub struct VecArray<T> {
+pub struct VecArray<T, const N: usize = MAX_ARRAY_SIZE> {
     /// Total number of values held.
     len: usize,
     /// Fixed-size storage for fast, no-allocation access.
-    array_store: [MaybeUninit<T>; MAX_ARRAY_SIZE],
+    array_store: [MaybeUninit<T>; N],
     /// Dynamic storage. For spill-overs.
     vec_store: Vec<T>,
+    /// Keep data in the `Vec`, once spilled over, until this `VecArray` is emptied?
+    sticky: bool,
+    /// Panic instead of spilling over into the `Vec`?
+    no_spill: bool,
 }
 
-/// Maximum slots of fixed-size storage for a `VecArray`.
+/// Default number of slots of fixed-size storage for a `VecArray`.
 /// Defaults to 4, which should be enough for many cases and is a good balance between
 /// memory consumption (for the fixed-size array) and reduced allocations.
 ///
 /// # Usage Considerations
 ///
-/// To alter this size right now, unfortunately you must clone this repo and modify the code directly.
-///
-/// This cannot be avoided until constant generics land in Rust.
+/// To use a different size, specify the constant generic parameter directly,
+/// e.g. `VecArray<T, 8>`.
 pub const MAX_ARRAY_SIZE: usize = 4;
 
-impl<T> Drop for VecArray<T> {
+/// Error returned when accessing an index that is out of bounds, e.g. by `VecArray::try_get`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexError {
+    /// The index that was accessed.
+    pub index: usize,
+    /// The number of items in the `VecArray` at the time of access.
+    pub len: usize,
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index {} OOB in VecArray with {} item(s)",
+            self.index, self.len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexError {}
+
+impl<T, const N: usize> Drop for VecArray<T, N> {
     fn drop(&mut self) {
         self.clear();
     }
 }
 
-impl<T: Hash> Hash for VecArray<T> {
+impl<T: Hash, const N: usize> Hash for VecArray<T, N> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.iter().for_each(|x| x.hash(state));
+        // Hash the same way as a slice, which includes the length
+        self.as_ref().hash(state);
     }
 }
 
-impl<T> Default for VecArray<T> {
+impl<T, const N: usize> Default for VecArray<T, N> {
     fn default() -> Self {
         Self {
             len: 0,
-            array_store: unsafe { mem::MaybeUninit::uninit().assume_init() },
+            array_store: [const { MaybeUninit::uninit() }; N],
             vec_store: Vec::new(),
+            sticky: false,
+            no_spill: false,
         }
     }
 }
 
-impl<T: PartialEq> PartialEq for VecArray<T> {
+impl<T: PartialEq, const N: usize> PartialEq for VecArray<T, N> {
     fn eq(&self, other: &Self) -> bool {
-        if self.len != other.len || self.vec_store != other.vec_store {
-            return false;
-        }
+        // Only compare initialized items
+        self.as_ref() == other.as_ref()
+    }
+}
 
-        if self.len > MAX_ARRAY_SIZE {
-            return true;
-        }
+impl<T: PartialEq, const N: usize> PartialEq<[T]> for VecArray<T, N> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_ref() == other
+    }
+}
 
-        unsafe {
-            mem::transmute::<_, &ArrayStore<T>>(&self.array_store)
-                == mem::transmute::<_, &ArrayStore<T>>(&other.array_store)
-        }
+impl<T: PartialEq, const N: usize> PartialEq<&[T]> for VecArray<T, N> {
+    fn eq(&self, other: &&[T]) -> bool {
+        self.as_ref() == *other
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<Vec<T>> for VecArray<T, N> {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.as_ref() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<[T; M]> for VecArray<T, N> {
+    fn eq(&self, other: &[T; M]) -> bool {
+        self.as_ref() == other
     }
 }
 
-impl<T: Clone> Clone for VecArray<T> {
+/// Cloning a `VecArray` whose items fit into the fixed-size array never allocates:
+/// the clone stores its items in the fixed-size array, with an empty, unallocated `Vec`.
+///
+/// Otherwise, the `Vec` is cloned, which allocates only as much as needed to hold all the items
+/// (i.e. spare capacity in the original is not copied).
+impl<T: Clone, const N: usize> Clone for VecArray<T, N> {
     fn clone(&self) -> Self {
         let mut value: Self = Default::default();
-        value.len = self.len;
+        value.sticky = self.sticky;
+        value.no_spill = self.no_spill;
 
-        if self.is_fixed_storage() {
-            for x in 0..self.len {
-                let item = self.array_store.get(x).unwrap();
-                let item_value: &T = unsafe { mem::transmute(item) };
-                value.array_store[x] = MaybeUninit::new(item_value.clone());
+        if self.len <= N {
+            // Clone into the fixed-size array even if data is kept in the Vec
+            for item in self.iter() {
+                value.push_item(item.clone());
             }
         } else {
             value.vec_store = self.vec_store.clone();
+            value.len = self.len;
         }
 
+        value.assert_invariants();
         value
     }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.sticky = source.sticky;
+        self.no_spill = source.no_spill;
+
+        if source.len <= N {
+            // Clear data in the Vec but keep its allocation
+            if !self.is_fixed_storage() {
+                self.clear();
+            }
+            self.truncate(source.len);
+
+            // Clone existing items in place
+            let len = self.len;
+            for (item, source_item) in self.iter_mut().zip(source.iter()) {
+                item.clone_from(source_item);
+            }
+            for item in &source.as_ref()[len..] {
+                self.push_item(item.clone());
+            }
+        } else {
+            if self.is_fixed_storage() {
+                self.clear();
+            }
+
+            // Reuse the existing allocation of the Vec
+            self.vec_store.clone_from(&source.vec_store);
+            self.len = self.vec_store.len();
+        }
+
+        self.assert_invariants();
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for VecArray<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for VecArray<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_ref().partial_cmp(other.as_ref())
+    }
 }
 
-impl<T: Eq> Eq for VecArray<T> {}
+impl<T: Ord, const N: usize> Ord for VecArray<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
 
-impl<T> FromIterator<T> for VecArray<T> {
+impl<T, const N: usize> FromIterator<T> for VecArray<T, N> {
     fn from_iter<X: IntoIterator<Item = T>>(iter: X) -> Self {
-        let mut vec = VecArray::new();
+        let iter = iter.into_iter();
 
-        for x in iter {
-            vec.push(x);
+        // Too many items for the fixed-size array - collect directly into the Vec
+        if iter.size_hint().0 > N {
+            return iter.collect::<Vec<_>>().into();
         }
 
+        let mut vec = VecArray::new();
+        vec.extend(iter);
         vec
     }
 }
 
-impl<T: 'static> IntoIterator for VecArray<T> {
+impl<T, const N: usize> Extend<T> for VecArray<T, N> {
+    fn extend<X: IntoIterator<Item = T>>(&mut self, iter: X) {
+        let mut iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        // Fill up the fixed-size array first
+        while self.is_fixed_storage() {
+            match iter.next() {
+                Some(x) => self.push_item(x),
+                None => return,
+            }
+        }
+
+        // Spilled over - add the rest directly into the Vec
+        self.extend_vec_store(|vec| vec.extend(iter));
+    }
+}
+
+impl<'a, T: Copy + 'a, const N: usize> Extend<&'a T> for VecArray<T, N> {
+    fn extend<X: IntoIterator<Item = &'a T>>(&mut self, iter: X) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, const N: usize> IntoIterator for VecArray<T, N> {
     type Item = T;
-    type IntoIter = Box<dyn Iterator<Item = T>>;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        IntoIter(if self.is_fixed_storage() {
+            let mut it = FixedStorageIterator::<T, N> {
+                data: [const { MaybeUninit::uninit() }; N],
+                index: 0,
+                limit: self.len,
+            };
+
+            for x in 0..self.len {
+                it.data[x] =
+                    mem::replace(self.array_store.get_mut(x).unwrap(), MaybeUninit::uninit());
+            }
+            self.len = 0;
+
+            IntoIterStore::Fixed(it)
+        } else {
+            IntoIterStore::Vec(Vec::from(self).into_iter())
+        })
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a VecArray<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut VecArray<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.into_iter()
+        self.iter_mut()
     }
 }
 
-impl<T> VecArray<T> {
+impl<T, const N: usize> VecArray<T, N> {
     /// Create a new `VecArray`.
     pub fn new() -> Self {
         Default::default()
     }
 
-    /// Empty the `VecArray`.
-    pub fn clear(&mut self) {
-        if self.is_fixed_storage() {
-            for x in 0..self.len {
-                self.extract_from_array_store(x);
+    /// Create a new `VecArray` with a capacity hint.
+    ///
+    /// If `capacity > N`, the `Vec` is pre-allocated with `capacity` slots so that
+    /// spilling over from the fixed-size array does not need to re-allocate.
+    /// Otherwise, this is the same as `new`.
+    ///
+    /// The capacity is only a hint.  The fixed-size array is always used first regardless.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut value: Self = Default::default();
+
+        if capacity > N {
+            value.vec_store = Vec::with_capacity(capacity);
+        }
+
+        value
+    }
+
+    /// Create a new `VecArray` from an array.
+    ///
+    /// The storage is decided once from the array's length `M`: if `M <= N`, the items are moved
+    /// into the fixed-size array; otherwise, the array is converted into the `Vec` in one go.
+    pub fn from_array<const M: usize>(array: [T; M]) -> Self {
+        if M <= N {
+            let mut value: Self = Default::default();
+
+            for (x, item) in IntoIterator::into_iter(array).enumerate() {
+                value.set_into_array_store(x, item, false);
+                value.len += 1;
             }
+
+            value.assert_invariants();
+
+            value
         } else {
-            self.vec_store.clear();
+            Vec::from(array).into()
         }
-        self.len = 0;
+    }
+
+    /// Create a new `VecArray` from a `Vec`, only if all the items fit into the fixed-size array.
+    ///
+    /// If the `Vec` holds no more than `N` items, they are moved into the fixed-size array.
+    /// Otherwise, the original `Vec` is returned as `Err`.
+    ///
+    /// `TryFrom<Vec<T>>` cannot be used for this because it is already implemented
+    /// (infallibly) via `From<Vec<T>>`, which moves the items into the `Vec` storage instead.
+    pub fn try_from_vec(value: Vec<T>) -> Result<Self, Vec<T>> {
+        if value.len() > N {
+            return Err(value);
+        }
+
+        let value: Self = value.into();
+        debug_assert!(value.is_fixed_storage());
+        Ok(value)
+    }
+
+    /// Create a new `VecArray` from an iterator, only if all the items fit into the fixed-size array.
+    ///
+    /// If the iterator yields no more than `N` items, they are returned as `Ok` without any allocation.
+    ///
+    /// Otherwise, iteration stops as soon as the item that does not fit is yielded.  The `N + 1` items
+    /// taken from the iterator (in order, and spilled over into the `Vec`) are returned as `Err`.
+    /// Items not yet yielded remain in the iterator -- pass `iter.by_ref()` in order to keep them.
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Self> {
+        let mut value: Self = Default::default();
+
+        for item in iter {
+            if let Err(item) = value.try_push(item) {
+                value.push(item);
+                return Err(value);
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Create a builder for a `VecArray` with custom settings.
+    ///
+    /// With no settings changed, the `VecArray` built is the same as one created by `new`.
+    pub fn builder() -> VecArrayBuilder<T, N> {
+        Default::default()
+    }
+
+    /// Create a new `VecArray` that keeps its data in the `Vec` once spilled over.
+    ///
+    /// Normally, data is moved back into the fixed-size array as soon as the number of items
+    /// drops to `N`.  A `VecArray` created by this method does not do so until it is emptied entirely.
+    ///
+    /// This avoids excessive moving and copying of data, plus re-allocations of the `Vec`,
+    /// when the number of items constantly straddles `N` -- at the expense of keeping
+    /// the `Vec` (which may be much larger than the number of items) allocated.
+    pub fn with_sticky_vec() -> Self {
+        let mut value: Self = Default::default();
+        value.sticky = true;
+        value
+    }
+
+    /// Create a new ring buffer holding at most `capacity` items.
+    ///
+    /// See `VecArrayRing` for details.
+    pub fn ring(capacity: usize) -> VecArrayRing<T, N> {
+        VecArrayRing {
+            items: Self::with_capacity(capacity),
+            capacity,
+            head: 0,
+        }
+    }
+
+    /// Turn no-spill mode on or off.
+    ///
+    /// In no-spill mode, adding an item when the fixed-size array is full (e.g. via `push`, `insert`
+    /// or `extend`) panics instead of spilling over into the `Vec`.  This is useful for catching
+    /// accidental allocations during testing, e.g. on a realtime thread that must never allocate.
+    ///
+    /// Use `try_push` to add items without panicking: it returns the item as `Err` when the
+    /// fixed-size array is full, regardless of this mode.
+    ///
+    /// No-spill mode only guards against spilling over.  If data is already stored in the `Vec`,
+    /// items can still be added to it.
+    pub fn set_no_spill(&mut self, no_spill: bool) {
+        self.no_spill = no_spill;
+    }
+
+    /// Empty the `VecArray`.
+    ///
+    /// Memory allocated by the `Vec`, if any, is kept.  Use `shrink_to_fit` to release it.
+    ///
+    /// The fixed-size array is used afterwards.  When it overflows again, the items are moved into
+    /// the kept `Vec` without re-allocation.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+
+        self.assert_invariants();
     }
 
     /// Extract a `MaybeUninit` into a concrete initialized type.
@@ -246,6 +548,17 @@ impl<T> VecArray<T> {
         }
     }
 
+    /// Check whether data can spill over from the fixed-size array into the `Vec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `VecArray` is in no-spill mode (see `set_no_spill`).
+    fn check_spill(&self) {
+        if self.no_spill {
+            panic!("cannot spill over into dynamic storage in no-spill mode in VecArray");
+        }
+    }
+
     /// Move item in the fixed-size array into the `Vec`.
     ///
     /// # Panics
@@ -267,42 +580,216 @@ impl<T> VecArray<T> {
         );
     }
 
+    /// Move all items in the `Vec` back into the fixed-size array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the items do not fit into the fixed-size array, or if the `Vec` does not hold all the items.
+    fn move_vec_into_fixed(&mut self) {
+        if self.len > N {
+            panic!("too many items for fixed storage in VecArray");
+        }
+        if self.vec_store.len() != self.len {
+            panic!("dynamic storage does not hold all items in VecArray");
+        }
+        for index in (0..self.len).rev() {
+            let item = self.vec_store.pop().unwrap();
+            self.array_store[index] = MaybeUninit::new(item);
+        }
+    }
+
+    /// Move all items in the `Vec` back into the fixed-size array if they fit into it,
+    /// unless this `VecArray` keeps its data in the `Vec` (see `with_sticky_vec`).
+    ///
+    /// This must be called by every method that reduces the number of items in the `Vec`.
+    fn maybe_demote(&mut self) {
+        if !self.is_fixed_storage() && self.len <= N && !self.sticky {
+            self.move_vec_into_fixed();
+        }
+        self.assert_invariants();
+    }
+
+    /// Check that data is stored where it should be.
+    ///
+    /// * If the `Vec` is empty, data is stored in the fixed-size array, so there must be no more than `N` items.
+    /// * Otherwise, the `Vec` must hold all the items, and there must be more than `N` of them
+    ///   unless this `VecArray` keeps its data in the `Vec` (see `with_sticky_vec`).
+    ///
+    /// This is called (directly, or via `maybe_demote`) at the end of every method that changes
+    /// the number of items or moves data between storages.
+    /// It is a no-op unless `debug_assertions` are on.
+    #[inline(always)]
+    fn assert_invariants(&self) {
+        #[cfg(debug_assertions)]
+        if self.is_fixed_storage() {
+            assert!(
+                self.len <= N,
+                "too many items for fixed storage in VecArray"
+            );
+        } else {
+            assert_eq!(
+                self.vec_store.len(),
+                self.len,
+                "dynamic storage does not hold all items in VecArray"
+            );
+            assert!(
+                self.len > N || self.sticky,
+                "items that fit into fixed storage are not moved back in VecArray"
+            );
+        }
+    }
+
     /// Is data stored in fixed-size storage?
+    ///
+    /// Data is stored in the `Vec` if and only if it is not empty.
     fn is_fixed_storage(&self) -> bool {
-        self.len <= MAX_ARRAY_SIZE
+        self.vec_store.is_empty()
+    }
+
+    /// Get the items in the fixed-size array as a slice.
+    ///
+    /// Only the first `len` slots, which are initialized, are covered by the slice.
+    fn fixed_slice(&self) -> &[T] {
+        debug_assert!(self.is_fixed_storage());
+        unsafe { slice::from_raw_parts(self.array_store.as_ptr() as *const T, self.len) }
+    }
+
+    /// Get the items in the fixed-size array as a mutable slice.
+    ///
+    /// Only the first `len` slots, which are initialized, are covered by the slice.
+    fn fixed_slice_mut(&mut self) -> &mut [T] {
+        debug_assert!(self.is_fixed_storage());
+        unsafe { slice::from_raw_parts_mut(self.array_store.as_mut_ptr() as *mut T, self.len) }
+    }
+
+    /// Hide all items, so that this `VecArray` appears empty until `unhide_items` is called.
+    ///
+    /// Returns whether the items are stored in the `Vec`.  If whatever is borrowing this `VecArray`
+    /// in the meantime is leaked (e.g. via `mem::forget`), the items are simply leaked as well.
+    fn hide_items(&mut self) -> bool {
+        let spilled = !self.is_fixed_storage();
+        if spilled {
+            unsafe { self.vec_store.set_len(0) };
+        }
+        self.len = 0;
+        spilled
+    }
+
+    /// Get a pointer to the first slot of the storage holding the items hidden by `hide_items`.
+    fn hidden_items_ptr(&mut self, spilled: bool) -> *mut T {
+        if spilled {
+            self.vec_store.as_mut_ptr()
+        } else {
+            self.array_store.as_mut_ptr() as *mut T
+        }
+    }
+
+    /// Make the first `len` items hidden by `hide_items` visible again.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` slots of the storage must hold initialized items.
+    unsafe fn unhide_items(&mut self, spilled: bool, len: usize) {
+        if spilled {
+            self.vec_store.set_len(len);
+        }
+        self.len = len;
+
+        self.maybe_demote();
     }
 
     /// Push a new value to the end of this `VecArray`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in no-spill mode (see `set_no_spill`) if the fixed-size array is full.
     pub fn push<X: Into<T>>(&mut self, value: X) {
-        if self.len == MAX_ARRAY_SIZE {
-            self.move_fixed_into_vec(MAX_ARRAY_SIZE);
-            self.vec_store.push(value.into());
+        // Convert the value first, so a panic does not leave this `VecArray` in an inconsistent state
+        self.push_item(value.into());
+    }
+
+    /// Push an already converted value to the end of this `VecArray`.
+    fn push_item(&mut self, value: T) {
+        if self.is_fixed_storage() && self.len == N {
+            self.check_spill();
+            self.move_fixed_into_vec(N);
+            self.vec_store.push(value);
         } else if self.is_fixed_storage() {
-            self.set_into_array_store(self.len, value.into(), false);
+            self.set_into_array_store(self.len, value, false);
         } else {
-            self.vec_store.push(value.into());
+            self.vec_store.push(value);
+        }
+        self.len += 1;
+
+        self.assert_invariants();
+    }
+
+    /// Add items directly into the `Vec`, which must already hold all the data.
+    ///
+    /// `len` is kept in step with the `Vec` even if adding the items panics.
+    fn extend_vec_store<F: FnOnce(&mut Vec<T>)>(&mut self, f: F) {
+        /// Syncs `len` with the `Vec` when dropped.
+        struct SyncLen<'a, T, const N: usize>(&'a mut VecArray<T, N>);
+
+        impl<T, const N: usize> Drop for SyncLen<'_, T, N> {
+            fn drop(&mut self) {
+                self.0.len = self.0.vec_store.len();
+                self.0.maybe_demote();
+            }
+        }
+
+        let guard = SyncLen(self);
+        f(&mut guard.0.vec_store);
+    }
+
+    /// Push a new value to the end of this `VecArray` only if it fits into the fixed-size array.
+    ///
+    /// This never spills over into the `Vec` and so never allocates.
+    /// If the fixed-size array is full (or data is already stored in the `Vec`),
+    /// the original value is returned as `Err`.
+    pub fn try_push<X: Into<T>>(&mut self, value: X) -> Result<(), X> {
+        if !self.is_fixed_storage() || self.len >= N {
+            return Err(value);
         }
+
+        self.set_into_array_store(self.len, value.into(), false);
         self.len += 1;
+
+        self.assert_invariants();
+        Ok(())
     }
 
     /// Insert a new value to this `VecArray` at a particular position.
+    ///
+    /// If the `index` is out of bounds, the value is added to the end instead.
+    /// Use `try_insert` to treat this as an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics in no-spill mode (see `set_no_spill`) if the fixed-size array is full.
     pub fn insert<X: Into<T>>(&mut self, index: usize, value: X) {
         let index = if index > self.len { self.len } else { index };
 
-        if self.len == MAX_ARRAY_SIZE {
-            self.move_fixed_into_vec(MAX_ARRAY_SIZE);
-            self.vec_store.insert(index, value.into());
+        // Convert the value first, so a panic does not leave this `VecArray` in an inconsistent state
+        let value = value.into();
+
+        if self.is_fixed_storage() && self.len == N {
+            self.check_spill();
+            self.move_fixed_into_vec(N);
+            self.vec_store.insert(index, value);
         } else if self.is_fixed_storage() {
             // Move all items one slot to the right
             for x in (index..self.len).rev() {
                 let orig_value = self.extract_from_array_store(x);
                 self.set_into_array_store(x + 1, orig_value, false);
             }
-            self.set_into_array_store(index, value.into(), false);
+            self.set_into_array_store(index, value, false);
         } else {
-            self.vec_store.insert(index, value.into());
+            self.vec_store.insert(index, value);
         }
         self.len += 1;
+
+        self.assert_invariants();
     }
 
     /// Pop a value from the end of this `VecArray`.
@@ -314,23 +801,33 @@ impl<T> VecArray<T> {
         Some(if self.is_fixed_storage() {
             let value = self.extract_from_array_store(self.len - 1);
             self.len -= 1;
+
+            self.assert_invariants();
+
             value
         } else {
             let value = self.vec_store.pop().unwrap();
             self.len -= 1;
 
-            // Move back to the fixed array
-            if self.vec_store.len() == MAX_ARRAY_SIZE {
-                for index in (0..MAX_ARRAY_SIZE).rev() {
-                    let item = self.vec_store.pop().unwrap();
-                    self.set_into_array_store(index, item, false);
-                }
-            }
+            self.maybe_demote();
 
             value
         })
     }
 
+    /// Insert a new value to this `VecArray` at a particular position, only if the `index` is valid.
+    ///
+    /// Unlike `insert`, the `index` is not clamped.  If `index > len`, the original value is
+    /// returned as `Err` and this `VecArray` is left unchanged.
+    pub fn try_insert<X: Into<T>>(&mut self, index: usize, value: X) -> Result<(), X> {
+        if index > self.len {
+            return Err(value);
+        }
+
+        self.insert(index, value);
+        Ok(())
+    }
+
     /// Remove a value from this `VecArray` at a particular position.
     pub fn remove(&mut self, index: usize) -> Option<T> {
         if index >= self.len {
@@ -347,250 +844,3224 @@ impl<T> VecArray<T> {
             }
             self.len -= 1;
 
+            self.assert_invariants();
+
             value
         } else {
             let value = self.vec_store.remove(index);
             self.len -= 1;
 
-            // Move back to the fixed array
-            if self.vec_store.len() == MAX_ARRAY_SIZE {
-                for index in (0..MAX_ARRAY_SIZE).rev() {
-                    let item = self.vec_store.pop().unwrap();
-                    self.set_into_array_store(index, item, false);
-                }
-            }
+            self.maybe_demote();
 
             value
         })
     }
 
-    /// Get the number of items in this `VecArray`.
-    pub fn len(&self) -> usize {
-        self.len
+    /// Remove a value from this `VecArray` at a particular position.
+    ///
+    /// Unlike `remove`, the `index` is returned as `Err` if it is out of bounds.
+    pub fn try_remove(&mut self, index: usize) -> Result<T, usize> {
+        self.remove(index).ok_or(index)
     }
 
-    /// Is this `VecArray` empty?
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+    /// Remove the first item in this `VecArray` that is equal to `item`.
+    ///
+    /// Returns the removed item, or `None` if no such item exists.
+    pub fn remove_item(&mut self, item: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let index = self.position(|x| x == item)?;
+        self.remove(index)
     }
 
-    /// Get a reference to the item at a particular index.
-    pub fn get(&self, index: usize) -> Option<&T> {
-        if index >= self.len {
-            return None;
-        }
+    /// Insert a new value to the front of this `VecArray`.
+    ///
+    /// This shifts all existing items one slot to the right, so it is O(n).
+    pub fn push_front<X: Into<T>>(&mut self, value: X) {
+        self.insert(0, value);
+    }
 
-        if self.is_fixed_storage() {
-            let array_store: &ArrayStore<T> = unsafe { mem::transmute(&self.array_store) };
-            array_store.get(index)
-        } else {
-            self.vec_store.get(index)
-        }
+    /// Remove a value from the front of this `VecArray`.
+    ///
+    /// This shifts all remaining items one slot to the left, so it is O(n).
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove(0)
     }
 
-    /// Get a mutable reference to the item at a particular index.
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    /// Remove a value from this `VecArray` at a particular position, replacing it with the last value.
+    ///
+    /// This does not preserve ordering, but is O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
         if index >= self.len {
-            return None;
+            panic!("index OOB in VecArray");
         }
 
         if self.is_fixed_storage() {
-            let array_store: &mut ArrayStore<T> = unsafe { mem::transmute(&mut self.array_store) };
-            array_store.get_mut(index)
+            let last = self.extract_from_array_store(self.len - 1);
+
+            let value = if index == self.len - 1 {
+                last
+            } else {
+                let value = self.extract_from_array_store(index);
+                self.set_into_array_store(index, last, false);
+                value
+            };
+            self.len -= 1;
+
+            self.assert_invariants();
+
+            value
         } else {
-            self.vec_store.get_mut(index)
+            let value = self.vec_store.swap_remove(index);
+            self.len -= 1;
+
+            self.maybe_demote();
+
+            value
         }
     }
 
-    /// Get an iterator to entries in the `VecArray`.
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
+    /// Swap two items in this `VecArray`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `a` or `b` is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a >= self.len || b >= self.len {
+            panic!("index OOB in VecArray");
+        }
+
         if self.is_fixed_storage() {
-            let array_store: &ArrayStore<T> = unsafe { mem::transmute(&self.array_store) };
-            array_store[..self.len].iter()
+            self.array_store.swap(a, b);
         } else {
-            self.vec_store.iter()
+            self.vec_store.swap(a, b);
         }
     }
 
-    /// Get a mutable iterator to entries in the `VecArray`.
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+    /// Reverse the order of items in this `VecArray`, in place.
+    ///
+    /// This works the same regardless of whether data is stored in the fixed-size array or the `Vec`.
+    pub fn reverse(&mut self) {
+        self.as_mut().reverse();
+    }
+
+    /// Rotate the items in this `VecArray` in place, such that the first `mid` items move to the end.
+    ///
+    /// This never changes the number of items or where they are stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the number of items.
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.as_mut().rotate_left(mid);
+    }
+
+    /// Rotate the items in this `VecArray` in place, such that the last `k` items move to the front.
+    ///
+    /// This never changes the number of items or where they are stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is greater than the number of items.
+    pub fn rotate_right(&mut self, k: usize) {
+        self.as_mut().rotate_right(k);
+    }
+
+    /// Overwrite all the items in this `VecArray` with clones of `value`.
+    ///
+    /// This never changes the number of items or where they are stored.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.as_mut().fill(value);
+    }
+
+    /// Overwrite all the items in this `VecArray` with values returned by calling `f`.
+    ///
+    /// This never changes the number of items or where they are stored.
+    pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        self.as_mut().fill_with(f);
+    }
+
+    /// Overwrite the items in this `VecArray`, in order, with values from an iterator.
+    ///
+    /// If the iterator yields fewer values than the number of items, the remaining items at the end
+    /// are left unchanged.  If it yields more, the extra values are not taken from the iterator.
+    ///
+    /// This never changes the number of items or where they are stored.
+    pub fn fill_from_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for (item, value) in self.iter_mut().zip(iter) {
+            *item = value;
+        }
+    }
+
+    /// Sort the items in this `VecArray`, preserving the order of equal items.
+    ///
+    /// Sorting never changes the number of items or where they are stored.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.as_mut().sort();
+    }
+
+    /// Sort the items in this `VecArray`, without preserving the order of equal items.
+    ///
+    /// Sorting never changes the number of items or where they are stored.
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.as_mut().sort_unstable();
+    }
+
+    /// Sort the items in this `VecArray` with a comparator function, preserving the order of equal items.
+    ///
+    /// Sorting never changes the number of items or where they are stored.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F) {
+        self.as_mut().sort_by(compare);
+    }
+
+    /// Sort the items in this `VecArray` with a key extraction function, preserving the order of equal items.
+    ///
+    /// Sorting never changes the number of items or where they are stored.
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        self.as_mut().sort_by_key(f);
+    }
+
+    /// Shorten this `VecArray` to `len` items, dropping the rest.
+    ///
+    /// If `len` is greater than or equal to the current number of items, this has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let tail_len = self.len - len;
+
+        // Shorten first, so that a panic while dropping an item cannot lead to a double drop
+        self.len = len;
+
         if self.is_fixed_storage() {
-            let array_store: &mut ArrayStore<T> = unsafe { mem::transmute(&mut self.array_store) };
-            array_store[..self.len].iter_mut()
+            unsafe {
+                let tail = self.array_store.as_mut_ptr().add(len) as *mut T;
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(tail, tail_len));
+            }
+
+            self.assert_invariants();
         } else {
-            self.vec_store.iter_mut()
+            self.vec_store.truncate(len);
+
+            self.maybe_demote();
         }
     }
 
-    /// Move all data into another `VecArray`, overwriting any data there.
-    /// The existing `VecArray` is empty after this operation.
-    pub fn transfer(&mut self, other: &mut Self) {
-        other.clear();
+    /// Insert clones of all the items in a slice at a particular position,
+    /// shifting all items after it to the right.
+    ///
+    /// If the total number of items exceeds `N`, data is moved into the `Vec` only once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `index` is out of bounds.
+    pub fn insert_from_slice(&mut self, index: usize, items: &[T])
+    where
+        T: Clone,
+    {
+        if index > self.len {
+            panic!("index OOB in VecArray");
+        }
 
-        if self.is_fixed_storage() {
-            let array_store2: &mut ArrayStore<T> =
-                unsafe { mem::transmute(&mut other.array_store) };
+        // Add the new items to the end, then rotate them into position
+        self.extend_from_slice(items);
+        self.as_mut()[index..].rotate_right(items.len());
+    }
 
-            for x in 0..self.len {
-                array_store2[x] = self.extract_from_array_store(x);
+    /// Add clones of all the items in a slice to the end of this `VecArray`.
+    ///
+    /// If the total number of items exceeds `N`, data is moved into the `Vec` once before
+    /// all the items are added to it in one go.
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        if self.len + other.len() <= N {
+            for item in other {
+                self.push_item(item.clone());
             }
+            return;
+        }
+
+        if self.is_fixed_storage() {
+            self.check_spill();
+        }
+
+        self.reserve(other.len());
+
+        if self.is_fixed_storage() {
+            self.move_fixed_into_vec(self.len);
+        }
+
+        self.extend_vec_store(|vec| vec.extend_from_slice(other));
+    }
+
+    /// Resize this `VecArray` to `new_len` items.
+    ///
+    /// If `new_len` is greater than the current number of items, clones of `value` are added to the end.
+    /// Otherwise, this `VecArray` is truncated.
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if new_len > self.len {
+            self.extend(iter::repeat_n(value, new_len - self.len));
         } else {
-            other.vec_store = mem::take(&mut self.vec_store);
+            self.truncate(new_len);
         }
+    }
 
-        other.len = self.len;
-        self.len = 0;
+    /// Resize this `VecArray` to `new_len` items.
+    ///
+    /// If `new_len` is greater than the current number of items, values returned by calling `f`
+    /// are added to the end.  Otherwise, this `VecArray` is truncated.
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, f: F) {
+        if new_len > self.len {
+            self.extend(iter::repeat_with(f).take(new_len - self.len));
+        } else {
+            self.truncate(new_len);
+        }
     }
-}
 
-impl<T: 'static> VecArray<T> {
-    /// Get a mutable iterator to entries in the `VecArray`.
-    pub fn into_iter(mut self) -> Box<dyn Iterator<Item = T>> {
-        if self.is_fixed_storage() {
-            let mut it = FixedStorageIterator {
-                data: unsafe { mem::MaybeUninit::uninit().assume_init() },
-                index: 0,
-                limit: self.len,
-            };
+    /// Retain only the items specified by the predicate, preserving their order.
+    ///
+    /// If the number of remaining items fits into the fixed-size array, they are moved back into it.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|item| f(item));
+    }
 
-            for x in 0..self.len {
-                it.data[x] =
-                    mem::replace(self.array_store.get_mut(x).unwrap(), MaybeUninit::uninit());
+    /// Retain only the items specified by the predicate, preserving their order.
+    ///
+    /// Unlike `retain`, the predicate may also modify the items.
+    ///
+    /// If the number of remaining items fits into the fixed-size array, they are moved back into it.
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let items = self.as_mut();
+        let mut kept = 0;
+
+        // Move all retained items to the front
+        for x in 0..items.len() {
+            if f(&mut items[x]) {
+                items.swap(kept, x);
+                kept += 1;
             }
-            self.len = 0;
+        }
 
-            Box::new(it)
-        } else {
-            Box::new(Vec::from(self).into_iter())
+        self.truncate(kept);
+    }
+
+    /// Resolve a range into `start` and `end` indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or if its end is out of bounds.
+    fn resolve_range<R: RangeBounds<usize>>(&self, range: R) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len,
+        };
+        if start > end {
+            panic!("range start is greater than range end in VecArray");
+        }
+        if end > self.len {
+            panic!("range end OOB in VecArray");
         }
+        (start, end)
     }
-}
 
-/// An iterator that takes control of the fixed-size storage of a `VecArray` and returns its values.
-struct FixedStorageIterator<T> {
-    data: [MaybeUninit<T>; MAX_ARRAY_SIZE],
-    index: usize,
-    limit: usize,
-}
+    /// Remove consecutive repeated items.
+    ///
+    /// If the number of remaining items fits into the fixed-size array, they are moved back into it.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
 
-impl<T> Iterator for FixedStorageIterator<T> {
-    type Item = T;
+    /// Remove consecutive items that resolve to the same key.
+    ///
+    /// Only the first item of each run of items with equal keys is kept, so the items need not
+    /// implement `PartialEq` themselves and may differ in everything but the key.
+    ///
+    /// If the number of remaining items fits into the fixed-size array, they are moved back into it.
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.limit {
-            None
-        } else {
-            self.index += 1;
+    /// Remove consecutive items that satisfy the given equality relation.
+    ///
+    /// `same_bucket` is called with each item and the item before it that is retained.
+    /// If it returns `true`, the item is removed.
+    ///
+    /// If the number of remaining items fits into the fixed-size array, they are moved back into it.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        if self.len <= 1 {
+            return;
+        }
 
-            let value = mem::replace(
-                self.data.get_mut(self.index - 1).unwrap(),
-                MaybeUninit::uninit(),
-            );
+        let items = self.as_mut();
+        let mut kept = 1;
 
-            unsafe { Some(value.assume_init()) }
+        // Move the first item of each run to the front
+        for x in 1..items.len() {
+            let (retained, rest) = items.split_at_mut(x);
+
+            if !same_bucket(&mut rest[0], &mut retained[kept - 1]) {
+                items.swap(kept, x);
+                kept += 1;
+            }
         }
+
+        self.truncate(kept);
     }
-}
 
-impl<T: Default> VecArray<T> {
-    /// Get the item at a particular index, replacing it with the default.
-    pub fn take(&mut self, index: usize) -> Option<T> {
-        if index >= self.len {
-            return None;
+    /// Remove the items in the specified range from this `VecArray`, returning them as an iterator.
+    ///
+    /// The drained items are removed even if the iterator is not fully consumed.
+    /// If the number of remaining items fits into the fixed-size array, they are moved back into it.
+    ///
+    /// If the iterator is leaked (e.g. via `mem::forget`), the `VecArray` is left empty and all its
+    /// items are leaked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or if its end is out of bounds.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, N> {
+        let (start, end) = self.resolve_range(range);
+        let len = self.len;
+        let spilled = self.hide_items();
+
+        Drain {
+            source: self,
+            spilled,
+            len,
+            start,
+            end,
+            front: start,
+            back: end,
+        }
+    }
+
+    /// Remove all items from this `VecArray`, returning them as an iterator.
+    ///
+    /// This is the same as `drain(..)`.  The `VecArray` is empty (and uses the fixed-size array)
+    /// after the iterator is dropped, even if it is not fully consumed, so it can be reused.
+    pub fn drain_all(&mut self) -> Drain<'_, T, N> {
+        self.drain(..)
+    }
+
+    /// Split this `VecArray` into two at the given index.
+    ///
+    /// Returns a new `VecArray` containing the items in the range `[at, len)`.
+    /// This `VecArray` is left with the items in the range `[0, at)`.
+    ///
+    /// Both halves use the fixed-size array if their items fit into it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is out of bounds.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        if at > self.len {
+            panic!("index OOB in VecArray");
         }
 
         if self.is_fixed_storage() {
-            self.array_store
-                .get_mut(index)
-                .map(|v| unsafe { mem::transmute(v) })
+            let mut other: Self = Default::default();
+
+            for x in at..self.len {
+                other.push(self.extract_from_array_store(x));
+            }
+            self.len = at;
+
+            self.assert_invariants();
+
+            other
         } else {
-            self.vec_store.get_mut(index)
+            let other = self.vec_store.split_off(at);
+            self.len = at;
+
+            self.maybe_demote();
+
+            other.into()
         }
-        .map(mem::take)
     }
-}
 
-impl<T: fmt::Debug> fmt::Debug for VecArray<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self.iter().collect::<Vec<_>>(), f)
+    /// Replace the items in the specified range with the items from `replace_with`.
+    ///
+    /// Returns the removed items as an iterator.  Unlike `Vec::splice`, the replacement is performed
+    /// eagerly, so dropping the returned iterator has no effect on this `VecArray`.
+    ///
+    /// The items are stored in the fixed-size array afterwards if they fit into it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or if its end is out of bounds.
+    pub fn splice<R: RangeBounds<usize>, I: IntoIterator<Item = T>>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> IntoIter<T, N> {
+        let (start, end) = self.resolve_range(range);
+
+        let mut tail = self.split_off(end);
+        let removed = self.split_off(start);
+        self.extend(replace_with);
+        self.append(&mut tail);
+
+        removed.into_iter()
     }
-}
 
-impl<T> AsRef<[T]> for VecArray<T> {
-    fn as_ref(&self) -> &[T] {
+    /// Remove the items specified by the predicate, returning them as an iterator.
+    ///
+    /// Items are visited in order and each one is removed as soon as the predicate returns `true`
+    /// for it, so the predicate may also modify the items that are kept.
+    /// The kept items are compacted in place in a single pass.  When the iterator is dropped, if
+    /// the number of remaining items fits into the fixed-size array, they are moved back into it.
+    ///
+    /// If the iterator is dropped before being fully consumed, the items not yet visited are kept.
+    /// If the iterator is leaked (e.g. via `mem::forget`), the `VecArray` is left empty and all its
+    /// items are leaked.
+    pub fn extract_if<F: FnMut(&mut T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, N, F> {
+        let len = self.len;
+        let spilled = self.hide_items();
+
+        ExtractIf {
+            source: self,
+            spilled,
+            len,
+            index: 0,
+            kept: 0,
+            pred,
+        }
+    }
+
+    /// Get the number of items in this `VecArray`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is this `VecArray` empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the remaining spare capacity of the storage in use, as a slice of `MaybeUninit<T>`.
+    ///
+    /// If data is stored in the fixed-size array, this is the unused tail of the fixed-size array,
+    /// even if the `Vec` has already been allocated (e.g. via `reserve`).  Otherwise, this is the
+    /// spare capacity of the `Vec`.
+    ///
+    /// Items written into the returned slice can be committed with `set_len`.
+    /// Data is never moved between the fixed-size array and the `Vec` by doing so, which means
+    /// that at most `N - len` items can be added this way while the fixed-size array is in use.
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
         if self.is_fixed_storage() {
-            let array_store: &ArrayStore<T> = unsafe { mem::transmute(&self.array_store) };
-            &array_store[..self.len]
+            &mut self.array_store[self.len..]
         } else {
-            &self.vec_store[..]
+            self.vec_store.spare_capacity_mut()
         }
     }
-}
 
-impl<T> AsMut<[T]> for VecArray<T> {
-    fn as_mut(&mut self) -> &mut [T] {
+    /// Force the number of items in this `VecArray` to `new_len`.
+    ///
+    /// If data is stored in the fixed-size array, the new length applies to the fixed-size array;
+    /// otherwise, it applies to the `Vec`.  No items are initialized or dropped.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that:
+    ///
+    /// * if data is stored in the fixed-size array, `new_len <= N`;
+    /// * if data is stored in the `Vec`, `new_len` does not exceed the capacity of the `Vec`;
+    /// * the first `new_len` slots of the storage in use are initialized (e.g. via `spare_capacity_mut`).
+    ///
+    /// Items beyond `new_len` are not dropped when shrinking, so they are leaked unless the caller
+    /// has already taken care of them.
+    ///
+    /// Setting the length of the `Vec` to `N` or less moves the items back into the fixed-size array,
+    /// just like other methods that remove items (unless this `VecArray` keeps its data in the `Vec`).
+    pub unsafe fn set_len(&mut self, new_len: usize) {
         if self.is_fixed_storage() {
-            let array_store: &mut ArrayStore<T> = unsafe { mem::transmute(&mut self.array_store) };
-            &mut array_store[..self.len]
+            debug_assert!(new_len <= N, "too many items for fixed storage in VecArray");
+            self.len = new_len;
         } else {
-            &mut self.vec_store[..]
+            self.vec_store.set_len(new_len);
+            self.len = new_len;
+            self.maybe_demote();
         }
+        self.assert_invariants();
     }
-}
 
-impl<T> Deref for VecArray<T> {
-    type Target = [T];
-    fn deref(&self) -> &Self::Target {
-        self.as_ref()
+    /// Get the number of items this `VecArray` can hold without re-allocating.
+    ///
+    /// When data is stored in the fixed-size array, this is `N` or the capacity already reserved
+    /// in the `Vec` (e.g. via `reserve` or `with_capacity`), whichever is larger, because spilling
+    /// over into a pre-allocated `Vec` does not re-allocate.
+    /// Otherwise, this is the capacity of the `Vec`.
+    pub fn capacity(&self) -> usize {
+        if self.is_fixed_storage() {
+            N.max(self.vec_store.capacity())
+        } else {
+            self.vec_store.capacity()
+        }
     }
-}
 
-impl<T> DerefMut for VecArray<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.as_mut()
+    /// Has data spilled over from the fixed-size array into the `Vec`?
+    ///
+    /// This is normally the same as `len > N`, but a `VecArray` created via `with_sticky_vec`
+    /// may keep data in the `Vec` even when it fits into the fixed-size array.
+    pub fn spilled(&self) -> bool {
+        !self.is_fixed_storage()
     }
-}
-
-impl<T> Index<usize> for VecArray<T> {
-    type Output = T;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        self.get(index).unwrap()
+    /// Get the number of items the fixed-size array can hold, i.e. `N`.
+    pub const fn inline_size() -> usize {
+        N
     }
-}
 
-impl<T> IndexMut<usize> for VecArray<T> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.get_mut(index).unwrap()
+    /// Estimate the total number of bytes held by this `VecArray`.
+    ///
+    /// This is the size of the `VecArray` itself (including the fixed-size array) plus
+    /// the memory allocated by the `Vec`, if any -- which may be non-zero even when data is stored
+    /// in the fixed-size array (e.g. after `reserve`).
+    ///
+    /// Memory allocated by the items themselves (e.g. the contents of a `String`) is not included.
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<Self>() + self.vec_store.capacity() * mem::size_of::<T>()
     }
-}
 
-impl<T> From<VecArray<T>> for Vec<T> {
-    fn from(mut value: VecArray<T>) -> Self {
-        if value.len <= MAX_ARRAY_SIZE {
-            value.move_fixed_into_vec(value.len);
+    /// Reserve capacity for at least `additional` more items.
+    ///
+    /// If the total number of items still fits in the fixed-size array, this is a no-op.
+    /// Otherwise, the `Vec` is allocated (or grown) so that it can hold all the items.
+    ///
+    /// Data already in the fixed-size array stays there until it actually overflows,
+    /// at which point it is moved into the pre-allocated `Vec` without re-allocation.
+    ///
+    /// In no-spill mode (see `set_no_spill`), the `Vec` is not allocated while data is stored
+    /// in the fixed-size array.
+    pub fn reserve(&mut self, additional: usize) {
+        if self.len + additional <= N || (self.no_spill && self.is_fixed_storage()) {
+            return;
         }
-        value.len = 0;
 
-        let mut arr = Self::new();
-        arr.append(&mut value.vec_store);
-        arr
+        if self.is_fixed_storage() {
+            self.vec_store.reserve(self.len + additional);
+        } else {
+            self.vec_store.reserve(additional);
+        }
     }
-}
-
-impl<T> From<Vec<T>> for VecArray<T> {
-    fn from(mut value: Vec<T>) -> Self {
-        let mut arr: Self = Default::default();
-        arr.len = value.len();
 
-        if arr.len <= MAX_ARRAY_SIZE {
-            for x in (0..arr.len).rev() {
-                arr.set_into_array_store(x, value.pop().unwrap(), false);
+    /// Reserve capacity for exactly `additional` more items.
+    ///
+    /// If the total number of items still fits in the fixed-size array, this is a no-op.
+    /// Otherwise, the `Vec` is allocated (or grown) so that it can hold all the items.
+    ///
+    /// Data already in the fixed-size array stays there until it actually overflows,
+    /// at which point it is moved into the pre-allocated `Vec` without re-allocation.
+    ///
+    /// In no-spill mode (see `set_no_spill`), the `Vec` is not allocated while data is stored
+    /// in the fixed-size array.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        if self.len + additional <= N || (self.no_spill && self.is_fixed_storage()) {
+            return;
+        }
+
+        if self.is_fixed_storage() {
+            self.vec_store.reserve_exact(self.len + additional);
+        } else {
+            self.vec_store.reserve_exact(additional);
+        }
+    }
+
+    /// Shrink the capacity of this `VecArray` as much as possible.
+    ///
+    /// If the items fit into the fixed-size array, they are moved back into it (if not already) and
+    /// any memory still allocated by the `Vec` is freed.
+    /// Otherwise, the `Vec` is shrunk to fit the items.
+    pub fn shrink_to_fit(&mut self) {
+        if !self.is_fixed_storage() && self.len <= N {
+            self.move_vec_into_fixed();
+        }
+
+        if self.is_fixed_storage() {
+            self.vec_store = Vec::new();
+        } else {
+            self.vec_store.shrink_to_fit();
+        }
+
+        self.assert_invariants();
+    }
+
+    /// Shrink the capacity of this `VecArray` with a lower bound.
+    ///
+    /// The capacity remains at least as large as both `min_capacity` and the number of items.
+    ///
+    /// If the items fit into the fixed-size array, they are moved back into it (if not already).
+    /// Any memory still allocated by the `Vec` is then freed if `min_capacity <= N`, or shrunk
+    /// towards `min_capacity` otherwise.  If the items do not fit, the `Vec` is shrunk
+    /// towards `min_capacity`.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if !self.is_fixed_storage() && self.len <= N {
+            self.move_vec_into_fixed();
+        }
+
+        if self.is_fixed_storage() && min_capacity <= N {
+            self.vec_store = Vec::new();
+        } else {
+            self.vec_store.shrink_to(min_capacity);
+        }
+
+        self.assert_invariants();
+    }
+
+    /// Get a reference to the item at a particular index.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+
+        if self.is_fixed_storage() {
+            self.fixed_slice().get(index)
+        } else {
+            self.vec_store.get(index)
+        }
+    }
+
+    /// Get a reference to the item at a particular index.
+    ///
+    /// Returns `Err` with the index and the number of items if the `index` is out of bounds.
+    pub fn try_get(&self, index: usize) -> Result<&T, IndexError> {
+        self.get(index).ok_or(IndexError {
+            index,
+            len: self.len,
+        })
+    }
+
+    /// Get a mutable reference to the item at a particular index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+
+        if self.is_fixed_storage() {
+            self.fixed_slice_mut().get_mut(index)
+        } else {
+            self.vec_store.get_mut(index)
+        }
+    }
+
+    /// Get a reference to the item at a particular index, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that `index < len`.  Otherwise, the behavior is undefined.
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T {
+        if self.is_fixed_storage() {
+            self.fixed_slice().get_unchecked(index)
+        } else {
+            self.vec_store.get_unchecked(index)
+        }
+    }
+
+    /// Get a mutable reference to the item at a particular index, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that `index < len`.  Otherwise, the behavior is undefined.
+    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T {
+        if self.is_fixed_storage() {
+            self.fixed_slice_mut().get_unchecked_mut(index)
+        } else {
+            self.vec_store.get_unchecked_mut(index)
+        }
+    }
+
+    /// Get mutable references to several items at once.
+    ///
+    /// Returns `None` if any of the `indices` is out of bounds, or if any two of them are equal.
+    pub fn get_many_mut<const K: usize>(&mut self, indices: [usize; K]) -> Option<[&mut T; K]> {
+        self.as_mut().get_disjoint_mut(indices).ok()
+    }
+
+    /// Get a reference to the first item, or `None` if this `VecArray` is empty.
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Get a mutable reference to the first item, or `None` if this `VecArray` is empty.
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Get a reference to the last item, or `None` if this `VecArray` is empty.
+    pub fn last(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get(self.len - 1)
+        }
+    }
+
+    /// Get a mutable reference to the last item, or `None` if this `VecArray` is empty.
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get_mut(self.len - 1)
+        }
+    }
+
+    /// Get a reference to the first item and a slice of the rest of the items,
+    /// or `None` if this `VecArray` is empty.
+    pub fn split_first(&self) -> Option<(&T, &[T])> {
+        self.as_ref().split_first()
+    }
+
+    /// Get a mutable reference to the first item and a mutable slice of the rest of the items,
+    /// or `None` if this `VecArray` is empty.
+    pub fn split_first_mut(&mut self) -> Option<(&mut T, &mut [T])> {
+        self.as_mut().split_first_mut()
+    }
+
+    /// Get a reference to the last item and a slice of the rest of the items,
+    /// or `None` if this `VecArray` is empty.
+    pub fn split_last(&self) -> Option<(&T, &[T])> {
+        self.as_ref().split_last()
+    }
+
+    /// Get a mutable reference to the last item and a mutable slice of the rest of the items,
+    /// or `None` if this `VecArray` is empty.
+    pub fn split_last_mut(&mut self) -> Option<(&mut T, &mut [T])> {
+        self.as_mut().split_last_mut()
+    }
+
+    /// Does this `VecArray` contain an item equal to `value`?
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.as_ref().contains(value)
+    }
+
+    /// Compare this `VecArray` with a slice, item by item, using a custom equality function.
+    ///
+    /// Returns `false` if the lengths differ.
+    pub fn eq_by<U, F: FnMut(&T, &U) -> bool>(&self, other: &[U], mut f: F) -> bool {
+        self.len == other.len() && self.iter().zip(other).all(|(a, b)| f(a, b))
+    }
+
+    /// Do this `VecArray` and another contain the same items, regardless of order?
+    ///
+    /// Duplicated items are counted, so both must hold each item the same number of times.
+    ///
+    /// This compares sorted clones of both `VecArray`s (leaving the originals untouched),
+    /// so it takes O(n log n) time.  No allocation is needed if the items fit into the fixed-size array.
+    pub fn eq_unordered(&self, other: &Self) -> bool
+    where
+        T: Ord + Clone,
+    {
+        if self.len != other.len {
+            return false;
+        }
+
+        let mut a = self.clone();
+        let mut b = other.clone();
+        a.sort_unstable();
+        b.sort_unstable();
+        a == b
+    }
+
+    /// Does this `VecArray` start with the items in `needle`?
+    pub fn starts_with(&self, needle: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        self.as_ref().starts_with(needle)
+    }
+
+    /// Does this `VecArray` end with the items in `needle`?
+    pub fn ends_with(&self, needle: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        self.as_ref().ends_with(needle)
+    }
+
+    /// Find the index of the first item that matches a predicate.
+    pub fn position<P: FnMut(&T) -> bool>(&self, pred: P) -> Option<usize> {
+        self.iter().position(pred)
+    }
+
+    /// Find the index of the last item that matches a predicate.
+    pub fn rposition<P: FnMut(&T) -> bool>(&self, pred: P) -> Option<usize> {
+        self.iter().rposition(pred)
+    }
+
+    /// Count the number of items that match a predicate.
+    pub fn count<P: FnMut(&T) -> bool>(&self, mut pred: P) -> usize {
+        self.iter().filter(|item| pred(item)).count()
+    }
+
+    /// Find the first item that matches a predicate.
+    pub fn find<P: FnMut(&T) -> bool>(&self, mut pred: P) -> Option<&T> {
+        self.iter().find(|item| pred(item))
+    }
+
+    /// Binary search this sorted `VecArray` for an item.
+    ///
+    /// Returns `Ok` with the index of a matching item if found.
+    /// Otherwise, returns `Err` with the index where the item could be inserted while maintaining order.
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.as_ref().binary_search(x)
+    }
+
+    /// Binary search this sorted `VecArray` with a comparator function.
+    ///
+    /// Returns `Ok` with the index of a matching item if found.
+    /// Otherwise, returns `Err` with the index where the item could be inserted while maintaining order.
+    pub fn binary_search_by<F: FnMut(&T) -> Ordering>(&self, f: F) -> Result<usize, usize> {
+        self.as_ref().binary_search_by(f)
+    }
+
+    /// Binary search this sorted `VecArray` with a key extraction function.
+    ///
+    /// Returns `Ok` with the index of a matching item if found.
+    /// Otherwise, returns `Err` with the index where the item could be inserted while maintaining order.
+    pub fn binary_search_by_key<K: Ord, F: FnMut(&T) -> K>(
+        &self,
+        key: &K,
+        f: F,
+    ) -> Result<usize, usize> {
+        self.as_ref().binary_search_by_key(key, f)
+    }
+
+    /// Get a slice of all the items in this `VecArray`.
+    pub fn as_slice(&self) -> &[T] {
+        self.as_ref()
+    }
+
+    /// Get a mutable slice of all the items in this `VecArray`.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.as_mut()
+    }
+
+    /// Get a raw pointer to the first item of the storage in use (the fixed-size array or the `Vec`).
+    ///
+    /// The first `len` items are valid for reads via the pointer.
+    ///
+    /// The pointer is invalidated by any operation that may move the items, including those that
+    /// move data between the fixed-size array and the `Vec`, as well as moving the `VecArray` itself
+    /// while data is stored in the fixed-size array.
+    pub fn as_ptr(&self) -> *const T {
+        if self.is_fixed_storage() {
+            self.array_store.as_ptr() as *const T
+        } else {
+            self.vec_store.as_ptr()
+        }
+    }
+
+    /// Get a mutable raw pointer to the first item of the storage in use (the fixed-size array or the `Vec`).
+    ///
+    /// The first `len` items are valid for reads and writes via the pointer.
+    ///
+    /// The pointer is invalidated by any operation that may move the items, including those that
+    /// move data between the fixed-size array and the `Vec`, as well as moving the `VecArray` itself
+    /// while data is stored in the fixed-size array.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        if self.is_fixed_storage() {
+            self.array_store.as_mut_ptr() as *mut T
+        } else {
+            self.vec_store.as_mut_ptr()
+        }
+    }
+
+    /// Get an iterator to entries in the `VecArray`.
+    ///
+    /// The iterator is double-ended, so `iter().rev()` visits the items from the last to the first.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(if self.is_fixed_storage() {
+            self.fixed_slice().iter()
+        } else {
+            self.vec_store.iter()
+        })
+    }
+
+    /// Get an iterator to entries in the `VecArray`, starting from a particular index.
+    ///
+    /// The index is clamped, so if `start >= len`, the iterator is simply empty instead of panicking.
+    pub fn iter_from(&self, start: usize) -> Iter<'_, T> {
+        let start = if start > self.len { self.len } else { start };
+        Iter(self.as_ref()[start..].iter())
+    }
+
+    /// Get a mutable iterator to entries in the `VecArray`.
+    ///
+    /// The iterator is double-ended, so `iter_mut().rev()` visits the items from the last to the first.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(if self.is_fixed_storage() {
+            self.fixed_slice_mut().iter_mut()
+        } else {
+            self.vec_store.iter_mut()
+        })
+    }
+
+    /// Get a mutable iterator to entries in the `VecArray`, from the last to the first.
+    ///
+    /// This is the same as `iter_mut().rev()`.
+    pub fn iter_mut_rev(&mut self) -> iter::Rev<IterMut<'_, T>> {
+        self.iter_mut().rev()
+    }
+
+    /// Get an iterator over non-overlapping chunks of `size` items.
+    ///
+    /// The last chunk is shorter if the number of items is not a multiple of `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn chunks(&self, size: usize) -> slice::Chunks<'_, T> {
+        self.as_ref().chunks(size)
+    }
+
+    /// Get a mutable iterator over non-overlapping chunks of `size` items.
+    ///
+    /// The last chunk is shorter if the number of items is not a multiple of `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn chunks_mut(&mut self, size: usize) -> slice::ChunksMut<'_, T> {
+        self.as_mut().chunks_mut(size)
+    }
+
+    /// Get an iterator over non-overlapping chunks of `size` items, starting from the end.
+    ///
+    /// The last chunk (i.e. the one at the front of the `VecArray`) is shorter if the number of
+    /// items is not a multiple of `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn rchunks(&self, size: usize) -> slice::RChunks<'_, T> {
+        self.as_ref().rchunks(size)
+    }
+
+    /// Get an iterator over all overlapping windows of `size` items.
+    ///
+    /// If there are fewer than `size` items, the iterator returns no values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn windows(&self, size: usize) -> slice::Windows<'_, T> {
+        self.as_ref().windows(size)
+    }
+
+    /// Divide this `VecArray` into two slices at an index.
+    ///
+    /// The first slice contains the items in the range `[0, mid)` and the second slice
+    /// contains the items in the range `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is out of bounds.
+    pub fn split_at(&self, mid: usize) -> (&[T], &[T]) {
+        if mid > self.len {
+            panic!("index OOB in VecArray");
+        }
+        self.as_ref().split_at(mid)
+    }
+
+    /// Divide this `VecArray` into two mutable slices at an index.
+    ///
+    /// The first slice contains the items in the range `[0, mid)` and the second slice
+    /// contains the items in the range `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is out of bounds.
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut [T], &mut [T]) {
+        if mid > self.len {
+            panic!("index OOB in VecArray");
+        }
+        self.as_mut().split_at_mut(mid)
+    }
+
+    /// Flatten a `VecArray` of slices (or collections that can be borrowed as slices)
+    /// into a single `Vec`.
+    pub fn concat<U: Clone>(&self) -> Vec<U>
+    where
+        T: Borrow<[U]>,
+    {
+        self.as_ref().concat()
+    }
+
+    /// Flatten a `VecArray` of slices (or collections that can be borrowed as slices)
+    /// into a single `Vec`, placing a separator between each of them.
+    pub fn join<U: Clone>(&self, separator: &U) -> Vec<U>
+    where
+        T: Borrow<[U]>,
+    {
+        self.as_ref().join(separator)
+    }
+
+    /// Convert this `VecArray` into a `VecArray` of another type by applying `f` to each item.
+    ///
+    /// The result stores its items in the fixed-size array if they fit, otherwise in the `Vec`.
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> VecArray<U, N> {
+        self.into_iter().map(f).collect()
+    }
+
+    /// Split this `VecArray` into two by a predicate, preserving the order of the items.
+    ///
+    /// Returns a `VecArray` with the items for which the predicate returns `true`,
+    /// and another with the items for which it returns `false`.
+    /// Each of them uses the fixed-size array if its items fit into it.
+    pub fn partition<F: FnMut(&T) -> bool>(self, mut f: F) -> (Self, Self) {
+        let mut matched: Self = Default::default();
+        let mut unmatched: Self = Default::default();
+
+        for item in self {
+            if f(&item) {
+                matched.push(item);
+            } else {
+                unmatched.push(item);
+            }
+        }
+
+        (matched, unmatched)
+    }
+
+    /// Copy all the items in this `VecArray` into a new `Vec` by cloning them.
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.as_ref().to_vec()
+    }
+
+    /// Convert this `VecArray` into a `Vec`.
+    ///
+    /// If data is already stored in the `Vec`, it is simply handed over without any allocation or copying.
+    /// Otherwise, the items in the fixed-size array are moved into a new `Vec`.
+    pub fn into_vec(self) -> Vec<T> {
+        self.into()
+    }
+
+    /// Decompose this `VecArray` into the number of items and a `Vec` holding all the items.
+    ///
+    /// If data is already stored in the `Vec`, it is handed over without any allocation or copying.
+    /// Otherwise, the items in the fixed-size array are moved into a newly-allocated `Vec`.
+    ///
+    /// The number of items is always the same as the length of the `Vec`.
+    pub fn into_parts(self) -> (usize, Vec<T>) {
+        let vec = self.into_vec();
+        (vec.len(), vec)
+    }
+
+    /// Reconstruct a `VecArray` from the parts returned by `into_parts`.
+    ///
+    /// The items are moved into the fixed-size array if they fit into it (freeing the `Vec`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is not the same as the length of the `Vec`.
+    pub fn from_parts(len: usize, vec: Vec<T>) -> Self {
+        if len != vec.len() {
+            panic!("length does not match the Vec in VecArray");
+        }
+        vec.into()
+    }
+
+    /// Convert this `VecArray` into a boxed slice, dropping any spare capacity.
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        self.into_vec().into_boxed_slice()
+    }
+
+    /// Move all data into another `VecArray`, overwriting any data there.
+    /// The existing `VecArray` is empty after this operation.
+    ///
+    /// Existing data in the other `VecArray` is dropped first.  Afterwards, every item is owned
+    /// by exactly one slot in the other `VecArray`, regardless of which storage either side used.
+    pub fn transfer(&mut self, other: &mut Self) {
+        // Drop all existing items - the other VecArray now uses the fixed-size array
+        // (with all slots uninitialized), although its Vec may still hold capacity
+        other.clear();
+
+        if self.is_fixed_storage() {
+            for x in 0..self.len {
+                // The slot is uninitialized, so it must be overwritten without dropping it
+                other.array_store[x] = MaybeUninit::new(self.extract_from_array_store(x));
             }
         } else {
-            arr.vec_store = value;
+            other.vec_store = mem::take(&mut self.vec_store);
         }
 
-        arr
+        other.len = self.len;
+        self.len = 0;
+
+        other.maybe_demote();
+        self.assert_invariants();
+    }
+
+    /// Move all data from another `VecArray` to the end of this `VecArray`.
+    /// The other `VecArray` is empty after this operation.
+    ///
+    /// Unlike `transfer`, existing data in this `VecArray` is kept.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        let total = self.len + other.len;
+
+        if total <= N && self.is_fixed_storage() {
+            if other.is_fixed_storage() {
+                for x in 0..other.len {
+                    let item = other.extract_from_array_store(x);
+                    self.push_item(item);
+                }
+            } else {
+                // Only a sticky `VecArray` keeps so few items in its Vec - move them straight
+                // into the fixed-size array, without allocating
+                for item in other.vec_store.drain(..) {
+                    self.push_item(item);
+                }
+            }
+        } else {
+            if self.is_fixed_storage() && total > N {
+                self.check_spill();
+            }
+
+            self.reserve(other.len);
+
+            if self.is_fixed_storage() {
+                self.move_fixed_into_vec(self.len);
+            }
+
+            if other.is_fixed_storage() {
+                for x in 0..other.len {
+                    let item = other.extract_from_array_store(x);
+                    self.vec_store.push(item);
+                }
+            } else {
+                self.vec_store.append(&mut other.vec_store);
+            }
+
+            self.len = total;
+
+            self.maybe_demote();
+        }
+
+        other.len = 0;
+
+        self.assert_invariants();
+        other.assert_invariants();
+    }
+}
+
+/// An iterator that takes control of the fixed-size storage of a `VecArray` and returns its values.
+///
+/// Slots in the range `[index, limit)` hold actual data.
+struct FixedStorageIterator<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    index: usize,
+    limit: usize,
+}
+
+impl<T, const N: usize> Iterator for FixedStorageIterator<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.limit {
+            None
+        } else {
+            self.index += 1;
+
+            let value = mem::replace(
+                self.data.get_mut(self.index - 1).unwrap(),
+                MaybeUninit::uninit(),
+            );
+
+            unsafe { Some(value.assume_init()) }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.limit - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for FixedStorageIterator<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.limit {
+            None
+        } else {
+            self.limit -= 1;
+
+            let value = mem::replace(
+                self.data.get_mut(self.limit).unwrap(),
+                MaybeUninit::uninit(),
+            );
+
+            unsafe { Some(value.assume_init()) }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for FixedStorageIterator<T, N> {
+    fn drop(&mut self) {
+        // Drop all the values not yet returned
+        self.for_each(drop);
+    }
+}
+
+/// Storage of an `IntoIter`.
+enum IntoIterStore<T, const N: usize> {
+    /// Data taken from the fixed-size array.
+    Fixed(FixedStorageIterator<T, N>),
+    /// Data taken from the `Vec`.
+    Vec(vec::IntoIter<T>),
+}
+
+/// A builder for a `VecArray` with custom settings, created by `VecArray::builder`.
+pub struct VecArrayBuilder<T, const N: usize = MAX_ARRAY_SIZE> {
+    /// Number of slots to pre-allocate in the `Vec`.
+    vec_capacity: usize,
+    /// Keep data in the `Vec` once spilled over?
+    sticky: bool,
+    /// Panic instead of spilling over into the `Vec`?
+    no_spill: bool,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, const N: usize> Default for VecArrayBuilder<T, N> {
+    fn default() -> Self {
+        Self {
+            vec_capacity: 0,
+            sticky: false,
+            no_spill: false,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> VecArrayBuilder<T, N> {
+    /// Pre-allocate the `Vec` with `capacity` slots.
+    ///
+    /// As with `VecArray::with_capacity`, nothing is allocated unless `capacity > N`.
+    /// Nothing is allocated in no-spill mode either.
+    ///
+    /// The fixed-size array is still used first.  Once it overflows, its items are moved into
+    /// the pre-allocated `Vec`.
+    pub fn vec_capacity(mut self, capacity: usize) -> Self {
+        self.vec_capacity = capacity;
+        self
+    }
+
+    /// Keep data in the `Vec` once spilled over, until the `VecArray` is emptied entirely.
+    ///
+    /// See `VecArray::with_sticky_vec` for details.
+    pub fn sticky(mut self, sticky: bool) -> Self {
+        self.sticky = sticky;
+        self
+    }
+
+    /// Panic instead of spilling over into the `Vec`.
+    ///
+    /// See `VecArray::set_no_spill` for details.
+    pub fn no_spill(mut self, no_spill: bool) -> Self {
+        self.no_spill = no_spill;
+        self
+    }
+
+    /// Build the `VecArray`.
+    pub fn build(self) -> VecArray<T, N> {
+        let mut value = if self.no_spill {
+            VecArray::new()
+        } else {
+            VecArray::with_capacity(self.vec_capacity)
+        };
+        value.sticky = self.sticky;
+        value.no_spill = self.no_spill;
+        value
+    }
+}
+
+/// A ring buffer holding at most a fixed number of items, created by `VecArray::ring`.
+///
+/// When the ring buffer is full, adding an item overwrites the oldest item in place, so it is O(1)
+/// and no items are shifted.  If the capacity is no more than `N`, the items always stay in the
+/// fixed-size array.  Otherwise, the `Vec` is pre-allocated with as many slots as the capacity.
+///
+/// Like a `VecDeque`, the items may wrap around the end of the storage, so they are accessed
+/// oldest first via `iter`, `get` or `as_slices`.
+#[derive(Clone)]
+pub struct VecArrayRing<T, const N: usize = MAX_ARRAY_SIZE> {
+    /// The items.  Unless the ring buffer is full, they are not wrapped around.
+    items: VecArray<T, N>,
+    /// Maximum number of items.
+    capacity: usize,
+    /// Index of the oldest item in `items`.  This is always zero unless the ring buffer is full.
+    head: usize,
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for VecArrayRing<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> VecArrayRing<T, N> {
+    /// Get the maximum number of items this ring buffer holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of items in this ring buffer.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Is this ring buffer empty?
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Has data spilled over from the fixed-size array into the `Vec`?
+    ///
+    /// See `VecArray::spilled` for details.
+    pub fn spilled(&self) -> bool {
+        self.items.spilled()
+    }
+
+    /// Push a new value to the end of this ring buffer, overwriting the oldest item if it is full.
+    pub fn push<X: Into<T>>(&mut self, value: X) {
+        self.push_overwrite(value);
+    }
+
+    /// Push a new value to the end of this ring buffer, returning the oldest item overwritten
+    /// to make room for it, if any.
+    ///
+    /// If the capacity is zero, the new value itself is returned.
+    pub fn push_overwrite<X: Into<T>>(&mut self, value: X) -> Option<T> {
+        // Convert the value first, so a panic does not leave the items in an inconsistent state
+        let value = value.into();
+
+        if self.capacity == 0 {
+            Some(value)
+        } else if self.items.len < self.capacity {
+            self.items.push_item(value);
+            None
+        } else {
+            let oldest = mem::replace(&mut self.items.as_mut()[self.head], value);
+            self.head = (self.head + 1) % self.capacity;
+            Some(oldest)
+        }
+    }
+
+    /// Remove the oldest item from this ring buffer.
+    ///
+    /// If the items wrap around the end of the storage, they are first rotated into order,
+    /// so this is O(n).
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.unwrap_items();
+        self.items.pop_front()
+    }
+
+    /// Get a reference to the item at a particular index, counting from the oldest item.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.items.len {
+            None
+        } else {
+            self.items.get((self.head + index) % self.items.len)
+        }
+    }
+
+    /// Get an iterator to the items in this ring buffer, oldest first.
+    pub fn iter(&self) -> iter::Chain<slice::Iter<'_, T>, slice::Iter<'_, T>> {
+        let (front, back) = self.as_slices();
+        front.iter().chain(back.iter())
+    }
+
+    /// Get the items in this ring buffer as two slices, which hold all the items oldest first
+    /// when joined together.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (back, front) = self.items.as_ref().split_at(self.head);
+        (front, back)
+    }
+
+    /// Get the items in this ring buffer as two mutable slices, which hold all the items oldest
+    /// first when joined together.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (back, front) = self.items.as_mut().split_at_mut(self.head);
+        (front, back)
+    }
+
+    /// Empty this ring buffer.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.head = 0;
+    }
+
+    /// Convert this ring buffer into a `VecArray` holding its items, oldest first.
+    pub fn into_inner(mut self) -> VecArray<T, N> {
+        self.unwrap_items();
+        self.items
+    }
+
+    /// Rotate the items into order if they wrap around the end of the storage.
+    fn unwrap_items(&mut self) {
+        if self.head != 0 {
+            self.items.rotate_left(self.head);
+            self.head = 0;
+        }
+    }
+}
+
+/// An iterator that moves items out of a `VecArray`, created by `VecArray::into_iter`.
+pub struct IntoIter<T, const N: usize = MAX_ARRAY_SIZE>(IntoIterStore<T, N>);
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterStore::Fixed(it) => it.next(),
+            IntoIterStore::Vec(it) => it.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IntoIterStore::Fixed(it) => it.size_hint(),
+            IntoIterStore::Vec(it) => it.size_hint(),
+        }
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterStore::Fixed(it) => it.next_back(),
+            IntoIterStore::Vec(it) => it.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+/// An iterator over references to the items of a `VecArray`, created by `VecArray::iter`.
+pub struct Iter<'a, T>(slice::Iter<'a, T>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// An iterator over mutable references to the items of a `VecArray`, created by `VecArray::iter_mut`.
+pub struct IterMut<'a, T>(slice::IterMut<'a, T>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// A draining iterator for `VecArray`, created by `VecArray::drain`.
+pub struct Drain<'a, T, const N: usize = MAX_ARRAY_SIZE> {
+    /// The `VecArray` being drained.  Its items are hidden until the iterator is dropped.
+    source: &'a mut VecArray<T, N>,
+    /// Are the items stored in the `Vec`?
+    spilled: bool,
+    /// Number of items before draining.
+    len: usize,
+    /// Start of the drained range.
+    start: usize,
+    /// End of the drained range.
+    end: usize,
+    /// Index of the next item to yield from the front.
+    front: usize,
+    /// Index after the next item to yield from the back.
+    back: usize,
+}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            None
+        } else {
+            let index = self.front;
+            self.front += 1;
+            Some(unsafe { ptr::read(self.source.hidden_items_ptr(self.spilled).add(index)) })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            None
+        } else {
+            self.back -= 1;
+            Some(unsafe { ptr::read(self.source.hidden_items_ptr(self.spilled).add(self.back)) })
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        /// Moves the items after the drained range into place, even if dropping an item panics.
+        struct MoveTail<'r, 'a, T, const N: usize>(&'r mut Drain<'a, T, N>);
+
+        impl<T, const N: usize> Drop for MoveTail<'_, '_, T, N> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let tail_len = drain.len - drain.end;
+
+                unsafe {
+                    let items = drain.source.hidden_items_ptr(drain.spilled);
+                    ptr::copy(items.add(drain.end), items.add(drain.start), tail_len);
+                    drain
+                        .source
+                        .unhide_items(drain.spilled, drain.start + tail_len);
+                }
+            }
+        }
+
+        let guard = MoveTail(self);
+        let drain = &mut *guard.0;
+        let remaining = drain.back - drain.front;
+        let index = drain.front;
+        drain.front = drain.back;
+
+        unsafe {
+            let items = drain.source.hidden_items_ptr(drain.spilled).add(index);
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(items, remaining));
+        }
+    }
+}
+
+/// An iterator that removes the items specified by a predicate, created by `VecArray::extract_if`.
+pub struct ExtractIf<'a, T, const N: usize, F: FnMut(&mut T) -> bool> {
+    /// The `VecArray` being extracted from.  Its items are hidden until the iterator is dropped.
+    source: &'a mut VecArray<T, N>,
+    /// Are the items stored in the `Vec`?
+    spilled: bool,
+    /// Number of items before extraction.
+    len: usize,
+    /// Index of the next item to visit.
+    index: usize,
+    /// Number of items kept so far, which are moved to the front.
+    kept: usize,
+    /// The predicate.
+    pred: F,
+}
+
+impl<T, const N: usize, F: FnMut(&mut T) -> bool> Iterator for ExtractIf<'_, T, N, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let items = self.source.hidden_items_ptr(self.spilled);
+
+        while self.index < self.len {
+            let index = self.index;
+            let item = unsafe { items.add(index) };
+            let extract = (self.pred)(unsafe { &mut *item });
+
+            // Only move on after the predicate returns, so the item is kept if it panics
+            self.index += 1;
+
+            if extract {
+                return Some(unsafe { ptr::read(item) });
+            }
+            if index != self.kept {
+                unsafe { ptr::copy_nonoverlapping(item, items.add(self.kept), 1) };
+            }
+            self.kept += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.len - self.index))
+    }
+}
+
+impl<T, const N: usize, F: FnMut(&mut T) -> bool> Drop for ExtractIf<'_, T, N, F> {
+    fn drop(&mut self) {
+        // Keep the items not yet visited
+        let tail_len = self.len - self.index;
+
+        unsafe {
+            let items = self.source.hidden_items_ptr(self.spilled);
+            ptr::copy(items.add(self.index), items.add(self.kept), tail_len);
+            self.source.unhide_items(self.spilled, self.kept + tail_len);
+        }
+    }
+}
+
+impl<T: Default, const N: usize> VecArray<T, N> {
+    /// Get the item at a particular index, replacing it with the default.
+    pub fn take(&mut self, index: usize) -> Option<T> {
+        self.get_mut(index).map(mem::take)
+    }
+
+    /// Add `n` default items to the end of this `VecArray`.
+    ///
+    /// If the total number of items exceeds `N`, the `Vec` is allocated only once to hold all of them.
+    pub fn extend_with_default(&mut self, n: usize) {
+        self.extend(iter::repeat_with(T::default).take(n));
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for VecArray<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for VecArray<T, N> {
+    fn as_ref(&self) -> &[T] {
+        if self.is_fixed_storage() {
+            self.fixed_slice()
+        } else {
+            &self.vec_store[..]
+        }
+    }
+}
+
+/// `Hash`, `Eq` and `Ord` all behave the same as for the slice, so a `VecArray` key in a
+/// `HashMap` or `BTreeMap` can be looked up with a slice.
+impl<T, const N: usize> Borrow<[T]> for VecArray<T, N> {
+    fn borrow(&self) -> &[T] {
+        self.as_ref()
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for VecArray<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        if self.is_fixed_storage() {
+            self.fixed_slice_mut()
+        } else {
+            &mut self.vec_store[..]
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for VecArray<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+impl<T, const N: usize> DerefMut for VecArray<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for VecArray<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for VecArray<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+/// Implement `Index` and `IndexMut` for a range type by slicing the items.
+macro_rules! impl_index_range {
+    ($($range:ty),*) => {
+        $(
+            impl<T, const N: usize> Index<$range> for VecArray<T, N> {
+                type Output = [T];
+
+                fn index(&self, index: $range) -> &Self::Output {
+                    &self.as_ref()[index]
+                }
+            }
+
+            impl<T, const N: usize> IndexMut<$range> for VecArray<T, N> {
+                fn index_mut(&mut self, index: $range) -> &mut Self::Output {
+                    &mut self.as_mut()[index]
+                }
+            }
+        )*
+    };
+}
+
+impl_index_range!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeToInclusive<usize>
+);
+
+impl<T, const N: usize> From<VecArray<T, N>> for Vec<T> {
+    fn from(mut value: VecArray<T, N>) -> Self {
+        // This includes a full fixed-size array with exactly `N` items
+        if value.is_fixed_storage() {
+            value.move_fixed_into_vec(value.len);
+        }
+        value.len = 0;
+
+        // Hand over the existing allocation
+        mem::take(&mut value.vec_store)
+    }
+}
+
+impl<T, const N: usize> From<Vec<T>> for VecArray<T, N> {
+    fn from(mut value: Vec<T>) -> Self {
+        let mut arr: Self = Default::default();
+        arr.len = value.len();
+
+        // Exactly `N` items still fit - the original Vec is then dropped, freeing its memory
+        if arr.len <= N {
+            for x in (0..arr.len).rev() {
+                arr.set_into_array_store(x, value.pop().unwrap(), false);
+            }
+        } else {
+            arr.vec_store = value;
+        }
+
+        arr.assert_invariants();
+        arr
+    }
+}
+
+impl<T, const N: usize> From<VecArray<T, N>> for VecDeque<T> {
+    fn from(value: VecArray<T, N>) -> Self {
+        // If data is already stored in the Vec, it is simply handed over
+        Vec::from(value).into()
+    }
+}
+
+impl<T, const N: usize> From<VecDeque<T>> for VecArray<T, N> {
+    fn from(value: VecDeque<T>) -> Self {
+        if value.len() <= N {
+            let mut arr: Self = Default::default();
+
+            for item in value {
+                arr.push(item);
+            }
+
+            arr
+        } else {
+            Vec::from(value).into()
+        }
+    }
+}
+
+impl<T, const N: usize, const M: usize> From<[T; M]> for VecArray<T, N> {
+    fn from(value: [T; M]) -> Self {
+        Self::from_array(value)
+    }
+}
+
+impl<T: Clone, const N: usize> From<&[T]> for VecArray<T, N> {
+    fn from(value: &[T]) -> Self {
+        if value.len() <= N {
+            let mut arr: Self = Default::default();
+
+            for item in value {
+                arr.push(item.clone());
+            }
+
+            arr
+        } else {
+            value.to_vec().into()
+        }
+    }
+}
+
+/// Bytes written are added to the end of the `VecArray`, spilling over into the `Vec` when
+/// the fixed-size array is full.
+#[cfg(feature = "std")]
+impl<const N: usize> std::io::Write for VecArray<u8, N> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::VecArray;
+    use core::{fmt, marker::PhantomData};
+    use serde::{
+        de::{SeqAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    impl<T: Serialize, const N: usize> Serialize for VecArray<T, N> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    /// A `Visitor` that builds a `VecArray` from a sequence.
+    struct VecArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for VecArrayVisitor<T, N> {
+        type Value = VecArray<T, N>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut value = VecArray::new();
+
+            while let Some(item) = seq.next_element::<T>()? {
+                value.push(item);
+            }
+
+            Ok(value)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for VecArray<T, N> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(VecArrayVisitor(PhantomData))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    /// An item that counts how many times it is dropped, optionally panicking when dropped.
+    struct Counted(Rc<Cell<usize>>, bool);
+
+    impl Counted {
+        fn new(drops: &Rc<Cell<usize>>) -> Self {
+            Self(drops.clone(), false)
+        }
+        fn panicking(drops: &Rc<Cell<usize>>) -> Self {
+            Self(drops.clone(), true)
+        }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+            if self.1 {
+                panic!("drop panicked");
+            }
+        }
+    }
+
+    #[test]
+    fn capacity_across_threshold() {
+        let mut value = VecArray::<i32>::new();
+        assert_eq!(value.capacity(), MAX_ARRAY_SIZE);
+
+        value.extend(0..4);
+        assert!(!value.spilled());
+        assert_eq!(value.capacity(), MAX_ARRAY_SIZE);
+
+        value.push(4);
+        assert!(value.spilled());
+        assert!(value.capacity() >= 5);
+    }
+
+    #[test]
+    fn capacity_after_reserve() {
+        let mut value = VecArray::<i32>::new();
+        value.extend(0..2);
+        value.reserve(10);
+        assert!(!value.spilled());
+        assert!(value.capacity() >= value.len() + 10);
+
+        let value = VecArray::<i32>::with_capacity(64);
+        assert!(value.capacity() >= 64);
+    }
+
+    #[test]
+    fn truncate_with_panicking_drop() {
+        for count in [4, 8] {
+            let drops = Rc::new(Cell::new(0));
+            let mut value = VecArray::<Counted>::new();
+            value.push(Counted::new(&drops));
+            value.push(Counted::panicking(&drops));
+            value.extend((2..count).map(|_| Counted::new(&drops)));
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| value.truncate(1)));
+            assert!(result.is_err());
+            assert_eq!(value.len(), 1);
+            assert_eq!(drops.get(), count - 1);
+
+            drop(value);
+            assert_eq!(drops.get(), count);
+        }
+    }
+
+    #[test]
+    fn clear_with_panicking_drop() {
+        for count in [4, 8] {
+            let drops = Rc::new(Cell::new(0));
+            let mut value = VecArray::<Counted>::new();
+            value.push(Counted::panicking(&drops));
+            value.extend((1..count).map(|_| Counted::new(&drops)));
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| value.clear()));
+            assert!(result.is_err());
+            assert!(value.is_empty());
+            assert_eq!(drops.get(), count);
+
+            drop(value);
+            assert_eq!(drops.get(), count);
+        }
+    }
+
+    #[test]
+    fn retain() {
+        let mut value: VecArray<i32> = (0..8).collect();
+        value.retain(|_| true);
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3, 4, 5, 6, 7][..]);
+        assert!(value.spilled());
+
+        value.retain(|&x| x % 3 == 0);
+        assert_eq!(value.as_ref(), &[0, 3, 6][..]);
+        assert!(!value.spilled());
+
+        value.retain(|&x| x > 0);
+        assert_eq!(value.as_ref(), &[3, 6][..]);
+
+        value.retain(|_| false);
+        assert!(value.is_empty());
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn drain_from_both_ends() {
+        for count in [4, 8] {
+            let mut value: VecArray<usize> = (0..count).collect();
+            let mut drain = value.drain(1..count - 1);
+            assert_eq!(drain.len(), count - 2);
+            assert_eq!(drain.next(), Some(1));
+            assert_eq!(drain.next_back(), Some(count - 2));
+            assert_eq!(
+                drain.by_ref().rev().collect::<Vec<_>>(),
+                (2..count - 2).rev().collect::<Vec<_>>()
+            );
+            assert_eq!(drain.next(), None);
+            assert_eq!(drain.next_back(), None);
+            drop(drain);
+
+            assert_eq!(value.as_ref(), &[0, count - 1][..]);
+            assert!(!value.spilled());
+        }
+    }
+
+    #[test]
+    fn drain_not_consumed() {
+        let mut value: VecArray<i32> = (0..10).collect();
+        assert_eq!(value.drain(2..4).next_back(), Some(3));
+        assert_eq!(value.as_ref(), &[0, 1, 4, 5, 6, 7, 8, 9][..]);
+        assert!(value.spilled());
+
+        value.drain(..6);
+        assert_eq!(value.as_ref(), &[8, 9][..]);
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn drain_with_panicking_drop() {
+        let drops = Rc::new(Cell::new(0));
+        let mut value = VecArray::<Counted>::new();
+        value.extend((0..3).map(|_| Counted::new(&drops)));
+        value.push(Counted::panicking(&drops));
+        value.extend((4..8).map(|_| Counted::new(&drops)));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(value.drain(2..5))));
+        assert!(result.is_err());
+        assert_eq!(value.len(), 5);
+        assert_eq!(drops.get(), 3);
+
+        drop(value);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn drain_leaked() {
+        for count in [4, 8] {
+            let mut value: VecArray<usize> = (0..count).collect();
+            let mut drain = value.drain(1..3);
+            assert_eq!(drain.next(), Some(1));
+            mem::forget(drain);
+
+            assert!(value.is_empty());
+            assert!(!value.spilled());
+            value.extend(0..6);
+            assert_eq!(value.as_ref(), &[0, 1, 2, 3, 4, 5][..]);
+        }
+    }
+
+    #[test]
+    fn resize_across_threshold() {
+        let mut value: VecArray<i32> = (0..2).collect();
+        value.resize(10, 42);
+        assert_eq!(value.as_ref(), &[0, 1, 42, 42, 42, 42, 42, 42, 42, 42][..]);
+        assert!(value.spilled());
+
+        value.resize(1, 0);
+        assert_eq!(value.as_ref(), &[0][..]);
+        assert!(!value.spilled());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for count in [0, 3, 4, 5, 10] {
+            let value: VecArray<u32> = (0..count).collect();
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(json, serde_json::to_string(value.as_ref()).unwrap());
+
+            let value2: VecArray<u32> = serde_json::from_str(&json).unwrap();
+            assert_eq!(value2, value);
+            assert_eq!(value2.spilled(), count > 4);
+        }
+    }
+
+    #[test]
+    fn eq_ignores_unused_slots() {
+        let mut value: VecArray<i32> = (0..4).collect();
+        value.pop();
+        value.pop();
+
+        let value2: VecArray<i32> = (0..2).collect();
+        assert_eq!(value, value2);
+
+        value.push(9);
+        assert_ne!(value, value2);
+    }
+
+    #[test]
+    fn into_iter_short_lived_references() {
+        for count in [3, 6] {
+            // The references borrow a local, so they are not `'static`
+            let items: Vec<usize> = (0..count).collect();
+            let value: VecArray<&usize> = items.iter().collect();
+            assert_eq!(value.into_iter().copied().collect::<Vec<_>>(), items);
+        }
+    }
+
+    #[test]
+    fn panicking_conversion() {
+        /// A value whose conversion into `Counted` panics.
+        struct Bomb;
+
+        impl From<Bomb> for Counted {
+            fn from(_: Bomb) -> Self {
+                panic!("conversion panicked");
+            }
+        }
+
+        for count in [3, 4, 6] {
+            let drops = Rc::new(Cell::new(0));
+            let mut value = VecArray::<Counted>::new();
+            value.extend((0..count).map(|_| Counted::new(&drops)));
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| value.insert(1, Bomb)));
+            assert!(result.is_err());
+            let result = panic::catch_unwind(AssertUnwindSafe(|| value.push(Bomb)));
+            assert!(result.is_err());
+            assert_eq!(value.len(), count);
+            assert_eq!(drops.get(), 0);
+
+            drop(value);
+            assert_eq!(drops.get(), count);
+        }
+    }
+
+    #[test]
+    fn extract_if() {
+        for count in [4, 10] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let extracted: Vec<i32> = value.extract_if(|x| *x % 3 != 0).collect();
+            assert_eq!(
+                extracted,
+                (0..count).filter(|x| x % 3 != 0).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                value.as_ref(),
+                &(0..count).step_by(3).collect::<Vec<_>>()[..]
+            );
+            assert!(!value.spilled());
+        }
+    }
+
+    #[test]
+    fn extract_if_not_consumed() {
+        let mut value: VecArray<i32> = (0..10).collect();
+        let mut extract = value.extract_if(|x| *x % 2 == 1);
+        assert_eq!(extract.next(), Some(1));
+        assert_eq!(extract.next(), Some(3));
+        drop(extract);
+        assert_eq!(value.as_ref(), &[0, 2, 4, 5, 6, 7, 8, 9][..]);
+
+        let mut extract = value.extract_if(|x| *x > 4);
+        assert_eq!(extract.next(), Some(5));
+        mem::forget(extract);
+        assert!(value.is_empty());
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn extract_if_with_panicking_predicate() {
+        let drops = Rc::new(Cell::new(0));
+        let mut value = VecArray::<Counted>::new();
+        value.extend((0..8).map(|_| Counted::new(&drops)));
+
+        let mut visited = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            value
+                .extract_if(|_| {
+                    visited += 1;
+                    if visited == 5 {
+                        panic!("predicate panicked");
+                    }
+                    visited % 2 == 0
+                })
+                .for_each(drop)
+        }));
+        assert!(result.is_err());
+        assert_eq!(value.len(), 6);
+        assert_eq!(drops.get(), 2);
+
+        drop(value);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn clone_from_all_storages() {
+        use std::string::{String, ToString};
+
+        for (count, source_count) in [(2, 3), (2, 8), (8, 3), (8, 10)] {
+            let mut value: VecArray<String> = (0..count).map(|x| x.to_string()).collect();
+            let source: VecArray<String> = (10..10 + source_count).map(|x| x.to_string()).collect();
+
+            value.clone_from(&source);
+            assert_eq!(value, source);
+            assert_eq!(value.spilled(), source_count > 4);
+        }
+    }
+
+    #[test]
+    fn clone_inline_does_not_allocate() {
+        let size = mem::size_of::<VecArray<i32>>();
+
+        let mut reserved = VecArray::<i32>::with_capacity(64);
+        reserved.extend(0..3);
+        assert!(reserved.memory_usage() > size);
+        assert_eq!(reserved.clone().memory_usage(), size);
+
+        let mut sticky = VecArray::<i32>::with_sticky_vec();
+        sticky.extend(0..8);
+        sticky.truncate(3);
+        assert!(sticky.spilled());
+        let value = sticky.clone();
+        assert!(!value.spilled());
+        assert_eq!(value.memory_usage(), size);
+        assert_eq!(value, sticky);
+    }
+
+    #[test]
+    fn splice_forces_spill() {
+        let mut value: VecArray<i32> = (0..3).collect();
+        let removed: Vec<i32> = value.splice(1..2, 10..16).collect();
+        assert_eq!(removed, [1]);
+        assert_eq!(value.as_ref(), &[0, 10, 11, 12, 13, 14, 15, 2][..]);
+        assert!(value.spilled());
+
+        let removed: Vec<i32> = value.splice(..7, iter::empty()).collect();
+        assert_eq!(removed, [0, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(value.as_ref(), &[2][..]);
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn from_iter_allocates_once() {
+        // Growing one item at a time would leave a power-of-two capacity
+        let value: VecArray<i32> = (0..1000).map(|x| x * 2).collect();
+        assert_eq!(value.len(), 1000);
+        assert_eq!(value.capacity(), 1000);
+
+        let value: VecArray<i32> = (0..3).collect();
+        assert_eq!(value.memory_usage(), mem::size_of::<VecArray<i32>>());
+    }
+
+    #[test]
+    fn transfer_all_storages() {
+        for (count, other_count) in [(3, 2), (3, 8), (8, 2), (8, 10)] {
+            let drops = Rc::new(Cell::new(0));
+            let mut value = VecArray::<Counted>::new();
+            value.extend((0..count).map(|_| Counted::new(&drops)));
+            let mut other = VecArray::<Counted>::new();
+            other.extend((0..other_count).map(|_| Counted::new(&drops)));
+
+            value.transfer(&mut other);
+            assert_eq!(drops.get(), other_count);
+            assert!(value.is_empty());
+            assert!(!value.spilled());
+            assert_eq!(other.len(), count);
+            assert_eq!(other.spilled(), count > 4);
+
+            drop(value);
+            assert_eq!(drops.get(), other_count);
+            drop(other);
+            assert_eq!(drops.get(), other_count + count);
+        }
+    }
+
+    #[test]
+    fn construct_drop_cycles() {
+        use std::string::{String, ToString};
+
+        for count in 0..10 {
+            let value = VecArray::<String>::new();
+            drop(value);
+
+            let value: VecArray<String> = (0..count).map(|x| x.to_string()).collect();
+            let value2 = value.clone();
+            drop(value);
+
+            let mut iter = value2.into_iter();
+            iter.next();
+            drop(iter);
+        }
+    }
+
+    #[test]
+    fn partially_filled_views() {
+        let mut value = VecArray::<i32>::new();
+        value.push(1);
+        value.push(2);
+
+        assert_eq!(value.as_ref(), &[1, 2][..]);
+        assert_eq!(value.iter().collect::<Vec<_>>(), [&1, &2]);
+        assert_eq!(value.get(1), Some(&2));
+        assert_eq!(value.get(2), None);
+
+        value.as_mut()[0] = 3;
+        value.iter_mut().for_each(|x| *x += 1);
+        assert_eq!(value.as_ref(), &[4, 3][..]);
+    }
+
+    #[test]
+    fn reverse_iteration() {
+        for count in [3, 8] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let reversed: Vec<i32> = (0..count).rev().collect();
+
+            assert_eq!(value.iter().rev().copied().collect::<Vec<_>>(), reversed);
+            value
+                .iter_mut()
+                .rev()
+                .enumerate()
+                .for_each(|(i, x)| *x = i as i32);
+            assert_eq!(value.as_ref(), &reversed[..]);
+        }
+    }
+
+    #[test]
+    fn clear_keeps_vec() {
+        let mut value: VecArray<i32> = (0..16).collect();
+        let capacity = value.capacity();
+        value.clear();
+        assert!(!value.spilled());
+        assert_eq!(value.capacity(), capacity);
+
+        value.extend(0..16);
+        assert!(value.spilled());
+        assert_eq!(value.capacity(), capacity);
+    }
+
+    #[test]
+    fn builder_allocation() {
+        let size = mem::size_of::<VecArray<i32>>();
+
+        let value = VecArray::<i32>::builder().vec_capacity(3).build();
+        assert_eq!(value.memory_usage(), size);
+
+        let value = VecArray::<i32>::builder()
+            .vec_capacity(64)
+            .no_spill(true)
+            .build();
+        assert_eq!(value.memory_usage(), size);
+
+        let value = VecArray::<i32>::builder().vec_capacity(64).build();
+        assert!(value.capacity() >= 64);
+    }
+
+    #[test]
+    fn dedup_by_key_keeps_first() {
+        let mut value: VecArray<(u32, char)> =
+            [(1, 'a'), (1, 'b'), (2, 'c'), (2, 'd'), (2, 'e'), (1, 'f')].into();
+        assert!(value.spilled());
+
+        value.dedup_by_key(|x| x.0);
+        assert_eq!(value.as_ref(), &[(1, 'a'), (2, 'c'), (1, 'f')][..]);
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn debug_format() {
+        use std::format;
+
+        let value = VecArray::<i32>::new();
+        assert_eq!(format!("{:?}", value), "[]");
+
+        let value: VecArray<i32> = (0..3).collect();
+        assert_eq!(format!("{:?}", value), "[0, 1, 2]");
+
+        let value: VecArray<i32> = (0..6).collect();
+        assert_eq!(format!("{:?}", value), format!("{:?}", value.to_vec()));
+    }
+
+    #[test]
+    fn shrinking_demotes() {
+        let spilled = || -> VecArray<i32> { (0..5).collect() };
+        let check = |value: VecArray<i32>| {
+            assert_eq!(value.len(), 4);
+            assert!(!value.spilled());
+        };
+
+        let mut value = spilled();
+        value.pop();
+        check(value);
+
+        let mut value = spilled();
+        value.remove(0);
+        check(value);
+
+        let mut value = spilled();
+        value.truncate(4);
+        check(value);
+
+        let mut value = spilled();
+        value.retain(|&x| x != 2);
+        check(value);
+
+        let mut value = spilled();
+        value.drain(1..2);
+        check(value);
+    }
+
+    #[test]
+    fn vec_conversions_at_threshold() {
+        let size = mem::size_of::<VecArray<i32>>();
+
+        let value: VecArray<i32> = Vec::from([0, 1, 2, 3]).into();
+        assert!(!value.spilled());
+        assert_eq!(value.memory_usage(), size);
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3][..]);
+
+        let vec: Vec<i32> = value.into();
+        assert_eq!(vec, [0, 1, 2, 3]);
+
+        let value: VecArray<i32> = Vec::from([0, 1, 2, 3, 4]).into();
+        assert!(value.spilled());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_write() {
+        use std::io::Write;
+
+        let mut value = VecArray::<u8>::new();
+        value.write_all(b"abc").unwrap();
+        assert_eq!(value.as_ref(), b"abc");
+        assert!(!value.spilled());
+
+        value.write_all(b"defgh").unwrap();
+        value.flush().unwrap();
+        assert_eq!(value.as_ref(), b"abcdefgh");
+        assert!(value.spilled());
+    }
+
+    #[test]
+    fn eq_unordered_multiset() {
+        let value: VecArray<i32> = [1, 2, 2, 3].into();
+        assert!(value.eq_unordered(&[2, 3, 1, 2].into()));
+        assert!(!value.eq_unordered(&[1, 2, 3, 3].into()));
+        assert!(!value.eq_unordered(&[1, 2, 3].into()));
+
+        let value: VecArray<i32> = [5, 1, 5, 1, 5, 1].into();
+        assert!(value.eq_unordered(&[1, 1, 1, 5, 5, 5].into()));
+        assert!(!value.eq_unordered(&[1, 1, 5, 5, 5, 5].into()));
+    }
+
+    #[test]
+    fn flags_fit_into_padding() {
+        // The flags fit into the padding after the fixed-size array
+        assert_eq!(
+            mem::size_of::<VecArray<u8>>(),
+            mem::size_of::<(usize, [u8; MAX_ARRAY_SIZE], Vec<u8>)>()
+        );
+    }
+
+    #[test]
+    fn ring() {
+        for capacity in [3, 4, 6] {
+            let mut value = VecArray::<usize>::ring(capacity);
+            for x in 0..capacity {
+                assert_eq!(value.push_overwrite(x), None);
+            }
+            let storage = value.items.as_ptr();
+            for x in capacity..20 {
+                assert_eq!(value.push_overwrite(x), Some(x - capacity));
+                assert_eq!(value.spilled(), capacity > 4);
+            }
+            // Overwritten in place - the storage has not moved
+            assert_eq!(value.items.as_ptr(), storage);
+
+            let items: Vec<usize> = (20 - capacity..20).collect();
+            assert_eq!(value.iter().copied().collect::<Vec<_>>(), items);
+            assert_eq!(value.get(0), Some(&(20 - capacity)));
+            assert_eq!(value.get(capacity - 1), Some(&19));
+            assert_eq!(value.get(capacity), None);
+            let (front, back) = value.as_slices();
+            assert_eq!([front, back].concat(), items);
+
+            value.push(20_usize);
+            assert_eq!(value.len(), capacity);
+            assert_eq!(value.pop_front(), Some(21 - capacity));
+            value.push(21_usize);
+            assert_eq!(value.len(), capacity);
+            let items: Vec<usize> = (22 - capacity..22).collect();
+            assert_eq!(value.clone().into_inner().as_ref(), &items[..]);
+            assert_eq!(std::format!("{:?}", value), std::format!("{:?}", items));
+        }
+
+        let mut value = VecArray::<i32>::ring(0);
+        assert_eq!(value.push_overwrite(1), Some(1));
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn extend_with_panicking_iterator() {
+        for count in [2, 6] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let items = (10..14).map(|x| {
+                if x == 13 {
+                    panic!("iterator panicked")
+                } else {
+                    x
+                }
+            });
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| value.extend(items)));
+            assert!(result.is_err());
+            assert_eq!(value.len(), count as usize + 3);
+            assert_eq!(value.len(), value.as_ref().len());
+            assert_eq!(&value.as_ref()[count as usize..], &[10, 11, 12][..]);
+
+            assert_eq!(value.pop(), Some(12));
+            value.push(42);
+            assert_eq!(value.last(), Some(&42));
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        for count in [3, 4, 7, 8] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let spilled = value.spilled();
+
+            value.reverse();
+            assert_eq!(value.as_ref(), &(0..count).rev().collect::<Vec<_>>()[..]);
+            assert_eq!(value.spilled(), spilled);
+        }
+    }
+
+    #[test]
+    fn dedup() {
+        let mut value: VecArray<i32> = [7; 8].into();
+        assert!(value.spilled());
+        value.dedup();
+        assert_eq!(value.as_ref(), &[7][..]);
+        assert!(!value.spilled());
+
+        let mut value: VecArray<i32> = (0..8).collect();
+        value.dedup();
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3, 4, 5, 6, 7][..]);
+        assert!(value.spilled());
+
+        let mut value: VecArray<i32> = [1, 3, 5, 2, 4, 7].into();
+        value.dedup_by(|a, b| *a % 2 == *b % 2);
+        assert_eq!(value.as_ref(), &[1, 2, 7][..]);
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn ordering_across_storages() {
+        let inline: VecArray<i32> = [1, 2, 3].into();
+        let spilled: VecArray<i32> = [1, 2, 3, 0, 0, 0].into();
+        assert!(!inline.spilled());
+        assert!(spilled.spilled());
+
+        assert!(inline < spilled);
+        assert_eq!(inline.cmp(&spilled), Ordering::Less);
+        assert_eq!(spilled.partial_cmp(&inline), Some(Ordering::Greater));
+
+        let larger: VecArray<i32> = [1, 3].into();
+        assert!(larger > spilled);
+        assert_eq!(larger.cmp(&spilled), [1, 3][..].cmp(spilled.as_ref()));
+    }
+
+    #[test]
+    fn hash_same_as_slice() {
+        use std::collections::{hash_map::DefaultHasher, HashMap};
+
+        fn hash<H: Hash + ?Sized>(value: &H) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        for count in [3, 8] {
+            let value: VecArray<i32> = (0..count).collect();
+            assert_eq!(hash(&value), hash(value.as_ref()));
+
+            let mut map = HashMap::new();
+            map.insert(value, count);
+            assert_eq!(map.get(&(0..count).collect::<Vec<_>>()[..]), Some(&count));
+        }
+    }
+
+    #[test]
+    fn append_all_storages() {
+        for (count, other_count) in [(2, 1), (3, 2), (2, 6), (6, 2), (6, 6)] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let mut other: VecArray<i32> = (count..count + other_count).collect();
+
+            value.append(&mut other);
+            assert_eq!(
+                value.as_ref(),
+                &(0..count + other_count).collect::<Vec<_>>()[..]
+            );
+            assert_eq!(value.spilled(), count + other_count > 4);
+            assert!(other.is_empty());
+            assert!(!other.spilled());
+        }
+    }
+
+    #[test]
+    fn append_sticky() {
+        let mut other = VecArray::<i32>::with_sticky_vec();
+        other.extend(0..8);
+        other.truncate(2);
+        assert!(other.spilled());
+
+        let mut value: VecArray<i32> = [9].into();
+        value.append(&mut other);
+        assert_eq!(value.as_ref(), &[9, 0, 1][..]);
+        assert!(!value.spilled());
+        assert!(other.is_empty());
+
+        let mut value = VecArray::<i32>::with_sticky_vec();
+        value.extend(0..8);
+        value.truncate(1);
+        value.append(&mut [5].into());
+        assert_eq!(value.as_ref(), &[0, 5][..]);
+        assert!(value.spilled());
+    }
+
+    #[test]
+    fn contains() {
+        for count in [3, 8] {
+            let value: VecArray<i32> = (0..count).collect();
+            assert!(value.contains(&0));
+            assert!(value.contains(&(count - 1)));
+            assert!(!value.contains(&count));
+        }
+
+        let mut value: VecArray<i32> = (0..4).collect();
+        value.pop();
+        assert!(!value.contains(&3));
+    }
+
+    #[test]
+    fn extend_from_slice_with_panicking_clone() {
+        /// An item whose clone panics if it holds `99`.
+        #[derive(Debug, PartialEq)]
+        struct P(i32);
+
+        impl Clone for P {
+            fn clone(&self) -> Self {
+                if self.0 == 99 {
+                    panic!("clone panicked");
+                }
+                P(self.0)
+            }
+        }
+
+        for (items, len) in [(&[P(1), P(2), P(99)][..], 5), (&[P(99), P(1)][..], 3)] {
+            let mut value: VecArray<P> = (0..3).map(P).collect();
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| value.extend_from_slice(items)));
+            assert!(result.is_err());
+            assert_eq!(value.len(), len);
+            assert_eq!(value.len(), value.as_slice().len());
+            assert_eq!(value.spilled(), len > 4);
+
+            assert_eq!(value.pop(), Some(P(2)));
+            value.push(P(42));
+            assert_eq!(value.last(), Some(&P(42)));
+        }
+
+        let mut value: VecArray<P> = (0..3).map(P).collect();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            value.insert_from_slice(0, &[P(1), P(99)])
+        }));
+        assert!(result.is_err());
+        assert_eq!(value.len(), value.as_slice().len());
+    }
+
+    #[test]
+    fn extend_from_large_slice() {
+        let items: Vec<i32> = (0..100).collect();
+        let mut value = VecArray::<i32>::new();
+        value.extend_from_slice(&items);
+        assert_eq!(value.as_ref(), &items[..]);
+        assert!(value.spilled());
+        assert!(value.capacity() >= 100);
+    }
+
+    #[test]
+    fn sort_keeps_storage() {
+        for count in [3, 8] {
+            let mut value: VecArray<i32> = (0..count).rev().collect();
+            let spilled = value.spilled();
+            let sorted: Vec<i32> = (0..count).collect();
+
+            value.sort();
+            assert_eq!(value.as_ref(), &sorted[..]);
+            value.sort_by(|a, b| b.cmp(a));
+            value.sort_unstable();
+            assert_eq!(value.as_ref(), &sorted[..]);
+            value.sort_by_key(|x| -x);
+            assert_eq!(value.first(), Some(&(count - 1)));
+
+            assert_eq!(value.len(), count as usize);
+            assert_eq!(value.spilled(), spilled);
+        }
+    }
+
+    #[test]
+    fn binary_search() {
+        for count in [3, 8] {
+            let value: VecArray<i32> = (0..count).map(|x| x * 10).collect();
+
+            assert_eq!(value.binary_search(&10), Ok(1));
+            assert_eq!(value.binary_search(&15), Err(2));
+            assert_eq!(value.binary_search(&-1), Err(0));
+            assert_eq!(value.binary_search(&(count * 10)), Err(count as usize));
+
+            assert_eq!(value.binary_search_by(|x| x.cmp(&20)), Ok(2));
+            assert_eq!(value.binary_search_by(|x| x.cmp(&25)), Err(3));
+            assert_eq!(value.binary_search_by_key(&2, |x| x / 10), Ok(2));
+            assert_eq!(value.binary_search_by_key(&-5, |x| x / 10), Err(0));
+        }
+    }
+
+    #[test]
+    fn eq_slices_vecs_and_arrays() {
+        let value: VecArray<i32> = [0, 1, 2].into();
+        assert_eq!(value, [0, 1, 2][..]);
+        assert_eq!(value, &[0, 1, 2][..]);
+        assert_eq!(value, Vec::from([0, 1, 2]));
+        assert_eq!(value, [0, 1, 2]);
+        assert_ne!(value, [0, 1]);
+        assert_ne!(value, Vec::from([0, 1, 3]));
+
+        let value: VecArray<i32> = (0..6).collect();
+        assert_eq!(value, [0, 1, 2, 3, 4, 5][..]);
+        assert_eq!(value, &[0, 1, 2, 3, 4, 5][..]);
+        assert_eq!(value, (0..6).collect::<Vec<_>>());
+        assert_eq!(value, [0, 1, 2, 3, 4, 5]);
+        assert_ne!(value, [0, 1, 2, 3, 4]);
+        assert_ne!(value, &[0, 1, 2, 3, 4, 6][..]);
+    }
+
+    #[test]
+    fn fill_keeps_storage() {
+        for count in [3, 8] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let spilled = value.spilled();
+
+            value.fill(7);
+            assert_eq!(value.as_ref(), &[7].repeat(count as usize)[..]);
+
+            let mut next = 0;
+            value.fill_with(|| {
+                next += 1;
+                next
+            });
+            assert_eq!(value.as_ref(), &(1..=count).collect::<Vec<_>>()[..]);
+
+            assert_eq!(value.len(), count as usize);
+            assert_eq!(value.spilled(), spilled);
+        }
+    }
+
+    #[test]
+    fn rotate() {
+        let mut value: VecArray<i32> = [0, 1, 2].into();
+        value.rotate_left(1);
+        assert_eq!(value.as_ref(), &[1, 2, 0][..]);
+        value.rotate_right(2);
+        assert_eq!(value.as_ref(), &[2, 0, 1][..]);
+        assert!(!value.spilled());
+
+        let mut value: VecArray<i32> = (0..6).collect();
+        value.rotate_left(2);
+        assert_eq!(value.as_ref(), &[2, 3, 4, 5, 0, 1][..]);
+        value.rotate_right(3);
+        assert_eq!(value.as_ref(), &[5, 0, 1, 2, 3, 4][..]);
+        assert!(value.spilled());
+    }
+
+    #[test]
+    fn try_push() {
+        let size = mem::size_of::<VecArray<i32>>();
+        let mut value = VecArray::<i32>::new();
+        for x in 0..4 {
+            assert_eq!(value.try_push(x), Ok(()));
+        }
+        assert_eq!(value.try_push(4), Err(4));
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3][..]);
+        assert_eq!(value.memory_usage(), size);
+
+        let mut value = VecArray::<i32>::with_sticky_vec();
+        value.extend(0..8);
+        value.truncate(1);
+        assert_eq!(value.try_push(1), Err(1));
+        assert_eq!(value.as_ref(), &[0][..]);
+    }
+
+    #[test]
+    fn position_and_find() {
+        for count in [4, 8] {
+            let value: VecArray<i32> = (0..count).map(|x| x % 2).collect();
+
+            assert_eq!(value.position(|&x| x == 1), Some(1));
+            assert_eq!(value.rposition(|&x| x == 0), Some(count as usize - 2));
+            assert_eq!(value.position(|&x| x == 2), None);
+            assert_eq!(value.rposition(|&x| x == 2), None);
+            assert_eq!(value.find(|&x| x > 0), Some(&1));
+            assert_eq!(value.find(|&x| x > 1), None);
+        }
+    }
+
+    #[test]
+    fn chunks_with_remainder() {
+        let mut value: VecArray<i32> = (0..7).collect();
+        let chunks: Vec<&[i32]> = value.chunks(3).collect();
+        assert_eq!(chunks, [&[0, 1, 2][..], &[3, 4, 5][..], &[6][..]]);
+
+        for chunk in value.chunks_mut(3) {
+            chunk[0] = -1;
+        }
+        assert_eq!(value.as_ref(), &[-1, 1, 2, -1, 4, 5, -1][..]);
+
+        let value: VecArray<i32> = (0..3).collect();
+        assert_eq!(value.chunks(2).last(), Some(&[2][..]));
+    }
+
+    #[test]
+    fn set_len_demotes() {
+        let mut value: VecArray<i32> = (0..8).collect();
+        assert!(value.spilled());
+        unsafe { value.set_len(3) };
+        assert_eq!(value.as_ref(), &[0, 1, 2][..]);
+        assert!(!value.spilled());
+
+        unsafe { value.set_len(1) };
+        assert_eq!(value.as_ref(), &[0][..]);
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn spare_capacity_mut() {
+        let mut value: VecArray<i32> = [0].into();
+        let spare = value.spare_capacity_mut();
+        assert_eq!(spare.len(), 3);
+        spare[0].write(1);
+        spare[1].write(2);
+        unsafe { value.set_len(3) };
+        assert_eq!(value.as_ref(), &[0, 1, 2][..]);
+        assert!(!value.spilled());
+
+        let mut value: VecArray<i32> = (0..6).collect();
+        value.reserve(2);
+        let spare = value.spare_capacity_mut();
+        assert!(spare.len() >= 2);
+        spare[0].write(6);
+        unsafe { value.set_len(7) };
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3, 4, 5, 6][..]);
+    }
+
+    #[test]
+    fn try_from_iter() {
+        let value = VecArray::<i32>::try_from_iter(0..4).unwrap();
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3][..]);
+        assert_eq!(value.memory_usage(), mem::size_of::<VecArray<i32>>());
+
+        let mut iter = 0..10;
+        let err = VecArray::<i32>::try_from_iter(iter.by_ref()).unwrap_err();
+        assert_eq!(err.as_ref(), &[0, 1, 2, 3, 4][..]);
+        assert_eq!(iter.next(), Some(5));
+    }
+
+    #[test]
+    fn drain_all_not_consumed() {
+        for count in [3, 8] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let mut drain = value.drain_all();
+            assert_eq!(drain.next(), Some(0));
+            assert_eq!(drain.next_back(), Some(count - 1));
+            drop(drain);
+
+            assert!(value.is_empty());
+            assert!(!value.spilled());
+            value.push(1);
+            assert_eq!(value.as_ref(), &[1][..]);
+        }
+    }
+
+    #[test]
+    fn partition() {
+        let value: VecArray<i32> = (0..6).collect();
+        let (small, large) = value.partition(|&x| x < 2);
+        assert_eq!(small.as_ref(), &[0, 1][..]);
+        assert_eq!(large.as_ref(), &[2, 3, 4, 5][..]);
+        assert!(!small.spilled());
+        assert!(!large.spilled());
+
+        let value: VecArray<i32> = (0..10).collect();
+        let (even, odd) = value.partition(|&x| x % 2 == 0);
+        assert_eq!(even.as_ref(), &[0, 2, 4, 6, 8][..]);
+        assert_eq!(odd.as_ref(), &[1, 3, 5, 7, 9][..]);
+        assert!(even.spilled());
+        assert!(odd.spilled());
+    }
+
+    #[test]
+    fn get_many_mut() {
+        for count in [3, 8] {
+            let mut value: VecArray<i32> = (0..count).collect();
+            let [a, b] = value.get_many_mut([0, 2]).unwrap();
+            mem::swap(a, b);
+            assert_eq!(&value.as_ref()[..3], &[2, 1, 0][..]);
+
+            assert!(value.get_many_mut([1, 1]).is_none());
+            assert!(value.get_many_mut([0, count as usize]).is_none());
+        }
+    }
+
+    #[test]
+    fn starts_with_and_ends_with() {
+        for count in [4, 5, 8] {
+            let value: VecArray<i32> = (0..count).collect();
+            let items: Vec<i32> = (0..count).collect();
+
+            assert!(value.starts_with(&[]));
+            assert!(value.starts_with(&items[..3]));
+            assert!(value.starts_with(&items));
+            assert!(!value.starts_with(&[1]));
+            assert!(value.ends_with(&items[count as usize - 3..]));
+            assert!(value.ends_with(&items));
+            assert!(!value.ends_with(&[0]));
+
+            let longer: Vec<i32> = (0..=count).collect();
+            assert!(!value.starts_with(&longer));
+            assert!(!value.ends_with(&longer));
+        }
+    }
+
+    #[test]
+    fn iter_from_clamps() {
+        for count in [3, 8] {
+            let value: VecArray<i32> = (0..count).collect();
+            let len = count as usize;
+
+            assert_eq!(
+                value.iter_from(1).copied().collect::<Vec<_>>(),
+                (1..count).collect::<Vec<_>>()
+            );
+            assert_eq!(value.iter_from(len - 1).collect::<Vec<_>>(), [&(count - 1)]);
+            assert_eq!(value.iter_from(len).next(), None);
+            assert_eq!(value.iter_from(len + 1).next(), None);
+            assert_eq!(value.iter_from(usize::MAX).len(), 0);
+        }
+    }
+
+    #[test]
+    fn split_first_and_last() {
+        let mut value = VecArray::<i32>::new();
+        assert_eq!(value.split_first(), None);
+        assert_eq!(value.split_last(), None);
+        assert!(value.split_first_mut().is_none());
+        assert!(value.split_last_mut().is_none());
+
+        value.push(1);
+        assert_eq!(value.split_first(), Some((&1, &[][..])));
+        assert_eq!(value.split_last(), Some((&1, &[][..])));
+        *value.split_first_mut().unwrap().0 = 2;
+        *value.split_last_mut().unwrap().0 += 1;
+        assert_eq!(value.as_ref(), &[3][..]);
+
+        let mut value: VecArray<i32> = (0..6).collect();
+        assert_eq!(value.split_first(), Some((&0, &[1, 2, 3, 4, 5][..])));
+        assert_eq!(value.split_last(), Some((&5, &[0, 1, 2, 3, 4][..])));
+        let (last, rest) = value.split_last_mut().unwrap();
+        rest[0] = *last;
+        assert_eq!(value.first(), Some(&5));
+    }
+
+    #[test]
+    fn rchunks_with_remainder() {
+        let value: VecArray<i32> = (0..7).collect();
+        let chunks: Vec<&[i32]> = value.rchunks(3).collect();
+        assert_eq!(chunks, [&[4, 5, 6][..], &[1, 2, 3][..], &[0][..]]);
+
+        let value: VecArray<i32> = (0..3).collect();
+        let chunks: Vec<&[i32]> = value.rchunks(2).collect();
+        assert_eq!(chunks, [&[1, 2][..], &[0][..]]);
+    }
+
+    #[test]
+    fn count() {
+        let value = VecArray::<i32>::new();
+        assert_eq!(value.count(|_| true), 0);
+
+        for (count, even) in [(4, 2), (9, 5)] {
+            let value: VecArray<i32> = (0..count).collect();
+            assert_eq!(value.count(|x| x % 2 == 0), even);
+            assert_eq!(value.count(|_| true), count as usize);
+            assert_eq!(value.count(|&x| x < 0), 0);
+        }
+    }
+
+    #[test]
+    fn try_get() {
+        use std::string::ToString;
+
+        for count in [3, 8] {
+            let value: VecArray<i32> = (0..count).collect();
+            let len = count as usize;
+
+            assert_eq!(value.try_get(1), Ok(&1));
+            let err = value.try_get(len).unwrap_err();
+            assert_eq!(err, IndexError { index: len, len });
+            assert_eq!(
+                err.to_string(),
+                std::format!("index {} OOB in VecArray with {} item(s)", len, len)
+            );
+        }
+    }
+
+    #[test]
+    fn extend_with_default() {
+        let mut value: VecArray<i32> = [1, 2].into();
+        value.extend_with_default(10);
+        assert_eq!(value.as_ref(), &[1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0][..]);
+        assert!(value.spilled());
+
+        let mut value: VecArray<i32> = [1].into();
+        value.extend_with_default(2);
+        assert_eq!(value.as_ref(), &[1, 0, 0][..]);
+        assert!(!value.spilled());
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut value: VecArray<i32> = [0, 2].into();
+        assert_eq!(value.try_insert(1, 1), Ok(()));
+        assert_eq!(value.try_insert(3, 3), Ok(()));
+        assert_eq!(value.try_insert(5, 5), Err(5));
+        assert_eq!(value.as_ref(), &[0, 1, 2, 3][..]);
+
+        assert_eq!(value.try_insert(0, -1), Ok(()));
+        assert!(value.spilled());
+        assert_eq!(value.try_insert(9, 9), Err(9));
+        assert_eq!(value.as_ref(), &[-1, 0, 1, 2, 3][..]);
+    }
+
+    #[test]
+    fn try_remove() {
+        let mut value = VecArray::<i32>::new();
+        assert_eq!(value.try_remove(0), Err(0));
+
+        let mut value: VecArray<i32> = (0..5).collect();
+        assert_eq!(value.try_remove(5), Err(5));
+        assert_eq!(value.try_remove(1), Ok(1));
+        assert!(!value.spilled());
+        assert_eq!(value.try_remove(3), Ok(4));
+        assert_eq!(value.try_remove(3), Err(3));
+        assert_eq!(value.as_ref(), &[0, 2, 3][..]);
+    }
+
+    /// Create a full `VecArray` in no-spill mode.
+    fn no_spill_full() -> VecArray<i32> {
+        let mut value = VecArray::builder().no_spill(true).build();
+        value.extend(0..4);
+        value
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot spill over")]
+    fn no_spill_push() {
+        no_spill_full().push(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot spill over")]
+    fn no_spill_insert() {
+        no_spill_full().insert(0, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot spill over")]
+    fn no_spill_extend() {
+        no_spill_full().extend(4..5);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot spill over")]
+    fn no_spill_extend_from_slice() {
+        no_spill_full().extend_from_slice(&[4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot spill over")]
+    fn no_spill_append() {
+        no_spill_full().append(&mut [4].into());
+    }
+
+    #[test]
+    fn no_spill_append_sticky() {
+        let mut other = VecArray::<i32>::with_sticky_vec();
+        other.extend(0..8);
+        other.truncate(2);
+
+        let mut value: VecArray<i32> = VecArray::builder().no_spill(true).build();
+        value.push(9);
+        value.append(&mut other);
+        assert_eq!(value.as_ref(), &[9, 0, 1][..]);
+        assert_eq!(value.memory_usage(), mem::size_of::<VecArray<i32>>());
+        assert!(other.is_empty());
+        assert!(!other.spilled());
     }
 }